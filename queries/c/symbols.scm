@@ -0,0 +1,55 @@
+; functions are named after the innermost declarator, e.g. `foo` of `char *foo(void) {}`
+(function_definition
+  declarator: (_
+    declarator: (identifier) @name)) @definition.function
+
+(function_definition
+  declarator: (_
+    declarator: (_
+      declarator: (identifier) @name))) @definition.function
+
+(function_definition
+  declarator: (_
+    declarator: (_
+      declarator: (_
+        declarator: (identifier) @name)))) @definition.function
+
+; `struct foo;` declares a struct defined elsewhere
+(struct_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.struct
+
+(struct_specifier
+  !name
+  body: (_)) @definition.struct
+
+(union_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.union
+
+(union_specifier
+  !name
+  body: (_)) @definition.union
+
+(enum_specifier
+  name: (type_identifier) @name
+  body: (_)) @definition.enum
+
+(enum_specifier
+  !name
+  body: (_)) @definition.enum
+
+; e.g. `typedef struct Vdbe Vdbe;`, `typedef char *string_t;`
+(type_definition
+  declarator: (type_identifier) @name) @definition.type
+
+(type_definition
+  declarator: (_
+    declarator: (type_identifier) @name)) @definition.type
+
+; e.g. `typedef int (*sqlite3_callback)(void *);`
+(type_definition
+  declarator: (_
+    declarator: (parenthesized_declarator
+      (_
+        declarator: (type_identifier) @name)))) @definition.type
@@ -0,0 +1,12 @@
+(function_declaration
+  name: (identifier) @name) @definition.function
+
+; methods are prefixed with the type of their receiver, e.g. `Server > Handle`
+(method_declaration
+  name: (field_identifier) @name) @definition.method
+
+(type_spec
+  name: (type_identifier) @name) @definition.type
+
+(type_alias
+  name: (type_identifier) @name) @definition.type
@@ -0,0 +1,20 @@
+(function_declaration
+  name: (identifier) @name) @definition.function
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(method_definition
+  name: (_) @name) @definition.method
+
+; `export function foo() {}` is named after the exported declaration.
+; `export { foo }` and `export default foo` export no declaration, so they are not symbols.
+(export_statement
+  declaration: (_
+    name: (_) @name)) @definition.export
+
+; e.g. `export const foo = async () => {}`
+(export_statement
+  declaration: (lexical_declaration
+    (variable_declarator
+      name: (identifier) @name))) @definition.export
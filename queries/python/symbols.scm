@@ -0,0 +1,10 @@
+(class_definition
+  name: (identifier) @name) @definition.class
+
+(function_definition
+  name: (identifier) @name) @definition.function
+
+; the TODO comment is placed above the decorators
+(decorated_definition
+  definition: (_
+    name: (identifier) @name)) @definition.decorated
@@ -0,0 +1,31 @@
+; `mod foo;` declares a module defined in another file
+(mod_item
+  name: (identifier) @name
+  body: (declaration_list)) @definition.module
+
+(enum_item
+  name: (type_identifier) @name) @definition.enum
+
+(struct_item
+  name: (type_identifier) @name) @definition.struct
+
+(trait_item
+  name: (type_identifier) @name) @definition.trait
+
+(function_item
+  name: (identifier) @name) @definition.function
+
+; a method of a trait without a default body, e.g. `fn from_node(node: Node) -> Range;`
+(function_signature_item
+  name: (identifier) @name) @definition.function
+
+(macro_definition
+  name: (identifier) @name) @definition.macro
+
+; `impl Foo for Bar` is named after the trait, `impl Bar` after the type
+(impl_item
+  trait: (_) @name) @definition.implementation
+
+(impl_item
+  !trait
+  type: (_) @name) @definition.implementation
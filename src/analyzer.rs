@@ -1,283 +1,982 @@
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use tree_sitter::{Node, Parser, Point, Range, Tree};
 
 use crate::grammar::get_language;
-use crate::language::Language;
-use crate::tokens::CommentToken;
-use crate::tree_sitter_extended::{MembershipCheck, RangeFactory, ResolveSymbol};
+use crate::language::{Language, MarkerPlacement, TestCode, TestPatterns};
+use crate::symbols::{find_symbol_definitions, SymbolDefinitions};
+use crate::tokens::Marker;
+use crate::tree_sitter_extended::{wrapped_declaration, RangeFactory, ResolveSymbol};
+
+const BOM: &str = "\u{feff}";
+
+/// Name given to a symbol whose name is out of the source code
+pub const UNRESOLVED_SYMBOL_NAME: &str = "<unknown>";
+
+thread_local! {
+    /// Parsers of each language, reused by every file analyzed on the thread
+    static PARSERS: RefCell<HashMap<String, Parser>> = RefCell::new(HashMap::new());
+}
 
 pub struct Analyzer {
     pub source_code: String,
     pub language: Language,
+    /// A unit of indentation of the source code, e.g. from `.editorconfig`.
+    /// The unit of the language is used if it is not given.
+    pub indent_unit: Option<String>,
+    /// Where TODO comments are placed. The placement of the language is used if it is not given.
+    pub marker_placement: Option<MarkerPlacement>,
 }
 
-impl<'tree> Analyzer {
-    fn get_indent_comment_pool(&self) -> Vec<String> {
-        let comment_token = CommentToken::from_language(&self.language);
-        let comment = comment_token.to_str();
-        let ident = match self.language {
-            Language::Ruby => "  ",
-            _ => "    ",
-        };
-        let max_ident_level = 100;
+/// A TODO comment which is placed above a symbol of the source code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Annotation {
+    /// Names of the enclosing scopes, followed by the name of the symbol itself
+    pub symbol_path: Vec<String>,
+    /// Treesitter node type of the symbol
+    pub kind: String,
+    /// Range of the treesitter node of the symbol
+    #[serde(serialize_with = "serialize_range")]
+    pub range: Range,
+    /// Row of the given source code that the TODO comment is placed above.
+    /// This is the row of the first decorator if the symbol is decorated,
+    /// or the first row of its doc comment if it has one and the TODO comment is not
+    /// a doc comment itself, unless the marker placement of the language says otherwise.
+    pub target_line: usize,
+    /// Row of the TODO comment in the analyzed source code
+    pub line: usize,
+    /// Number of enclosing blocks. The TODO comment is indented by this only if
+    /// the symbol shares its line with other code, e.g. `class Foo { void bar() {} }`
+    pub depth: usize,
+    /// State of the TODO comment, one of the states of the marker template
+    pub state: String,
+    /// Whether the symbol is test code, or is inside test code
+    pub is_test: bool,
+}
 
-        (0..max_ident_level)
-            .map(|level| {
-                let indent = ident.repeat(level);
-                format!("{}{}", indent, comment)
-            })
-            .collect()
-    }
+fn serialize_range<S: Serializer>(range: &Range, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("Range", 4)?;
+    state.serialize_field("start_byte", &range.start_byte)?;
+    state.serialize_field("end_byte", &range.end_byte)?;
+    state.serialize_field(
+        "start_point",
+        &(range.start_point.row, range.start_point.column),
+    )?;
+    state.serialize_field("end_point", &(range.end_point.row, range.end_point.column))?;
+    state.end()
+}
 
-    fn get_syntax_tree(&self) -> Tree {
-        let parser = RefCell::new(Parser::new());
-        let language = get_language(self.language.as_str()).unwrap();
+impl<'tree> Analyzer {
+    fn indent_unit(&self) -> &str {
+        self.indent_unit
+            .as_deref()
+            .unwrap_or_else(|| self.language.indent_unit())
+    }
 
-        let mut ts_parser = parser.borrow_mut();
-        ts_parser
-            .set_language(language)
-            .expect("treesitter parser for given language does not exists");
+    /// Takes the indentation of a TODO comment from the line it is placed above
+    fn indentation_of(&self, annotation: &Annotation, lines: &[&str]) -> String {
+        let line = lines[annotation.target_line];
+        let leading_whitespace = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
 
-        let tree = ts_parser.parse(&self.source_code, None);
+        let start_point = annotation.range.start_point;
+        let is_inline = annotation.target_line == start_point.row
+            && start_point.column > leading_whitespace.len();
 
-        tree.expect("Failed to parsing given source code")
+        if is_inline {
+            self.indent_unit().repeat(annotation.depth)
+        } else {
+            leading_whitespace.to_string()
+        }
     }
 
-    pub fn analyze(&self) -> VecDeque<String> {
-        let tree = self.get_syntax_tree();
-        let nodes = self.get_scannable_nodes(&tree);
+    fn get_syntax_tree(&self, source_code: &str) -> Result<Tree> {
+        let language_name = self.language.grammar_name();
 
-        let ignorable_node_types = self.language.ignorable_node_types();
+        PARSERS.with(|parsers| {
+            let mut parsers = parsers.borrow_mut();
 
-        let nested_traversable_symbols = self.language.nested_traversable_symbols();
+            let parser = match parsers.entry(language_name.to_string()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mut parser = Parser::new();
+                    parser
+                        .set_language(get_language(language_name)?)
+                        .with_context(|| {
+                            format!("treesitter parser for {} is incompatible", language_name)
+                        })?;
 
-        let mut writer_queue = VecDeque::new();
-        let mut pending_queue = VecDeque::new();
-        let mut nodes_queue = VecDeque::from(nodes);
-        let mut indentation_context: VecDeque<(Node, String)> = VecDeque::new();
-        let indent_comment_pool = self.get_indent_comment_pool();
-        let mut latest_comment_line = "";
-        let mut latest_comment_line_index = -1_isize;
-
-        let mut lines = vec![];
-        for line in self.source_code.lines() {
-            lines.push(line.to_string());
-        }
+                    entry.insert(parser)
+                }
+            };
 
-        for (i, line) in lines.iter().enumerate() {
-            let row = i;
-            let line_idx = i as isize;
-            let column = line.len();
+            parser
+                .parse(source_code, None)
+                .ok_or_else(|| anyhow!("Failed to parse the source code"))
+        })
+    }
 
-            let cursor_position = Point { row, column };
+    /// Finds the symbols with the `symbols.scm` query of the language, if there is one
+    fn get_symbol_definitions(&self, tree: &Tree, source_code: &str) -> Option<SymbolDefinitions> {
+        let query = self.language.symbols_query(tree.language())?;
 
-            if nodes_queue.is_empty() {
-                writer_queue.push_back(line.to_owned());
-                continue;
-            }
+        Some(find_symbol_definitions(
+            query,
+            tree.root_node(),
+            source_code,
+        ))
+    }
 
-            let (current_node, (row, from, to)) = match nodes_queue.front() {
-                Some(item) => item,
-                None => panic!("Failed to retrieve treesitter node from queue"),
-            };
+    /// Returns the source code with TODO comments, line by line
+    ///
+    /// TODO comments which are already in the source code are reconciled with the symbols:
+    /// the state (`[TODO]` or `[DONE]`) of the comment above a symbol is kept while its
+    /// symbol path is updated, symbols without a comment get a new `[TODO]` comment,
+    /// and comments of symbols which no longer exist are removed.
+    ///
+    /// A comment is taken for a TODO comment of balpan if its path is the path of the symbol
+    /// below it. A comment whose path names no symbol is taken for the one of a renamed symbol
+    /// if it is right above a symbol of the same scope, or of a deleted symbol if its scope is
+    /// the symbol enclosing it. The scope of a renamed symbol is matched by its old path,
+    /// and a path at the top level must be a single name. Any other comment which looks like
+    /// a TODO comment, e.g. `# [TODO] drop py2 support` or `// [DONE] checked overflows`
+    /// in the body of a function, is left as it is.
+    ///
+    /// Fails if the source code can't be parsed, or if the name of a symbol can't be resolved.
+    pub fn analyze(&self) -> Result<VecDeque<String>> {
+        let (_, writer_queue, _) = self.reconcile()?;
 
-            let mut symbol_name_with_context = String::new();
-
-            let mut pop_node = false;
-
-            match Range::from_node(*current_node) {
-                node_range if cursor_position.is_member_of(node_range) => {
-                    let node_type = current_node.kind();
-
-                    // rust specific code
-                    if node_type == "mod_item"
-                        && node_range.start_point.row == node_range.end_point.row
-                    {
-                        while !pending_queue.is_empty() {
-                            let decorator_line: &str = pending_queue.pop_front().unwrap();
-                            writer_queue.push_back(decorator_line.to_owned());
-                        }
-                        writer_queue.push_back(line.to_owned());
-                        nodes_queue.pop_front();
-                        continue;
-                    }
+        Ok(writer_queue)
+    }
 
-                    if ignorable_node_types.contains(&node_type) {
-                        while !pending_queue.is_empty() {
-                            let decorator_line: &str = pending_queue.pop_front().unwrap();
-                            writer_queue.push_back(decorator_line.to_owned());
-                        }
-                        writer_queue.push_back(line.to_owned());
-                        nodes_queue.pop_front();
-                        continue;
-                    }
+    /// Returns the source code with TODO comments, as a whole
+    ///
+    /// Only the lines of TODO comments are inserted or removed. Every other byte is kept
+    /// as it is, including a BOM, the line endings and a missing newline at the end of
+    /// the file. Inserted lines end with the line ending used the most in the source code.
+    pub fn annotate(&self) -> Result<String> {
+        let (_, _, annotated) = self.reconcile()?;
 
-                    if node_type == self.language.decorator_node_type() {
-                        pending_queue.push_back(line);
-                    } else {
-                        for (_node, node_symbol) in indentation_context.iter() {
-                            symbol_name_with_context
-                                .push_str(&format!("{} > ", node_symbol).to_string());
-                        }
-
-                        let node_symbol_with_indent = &lines[*row];
-                        let node_symbol = &node_symbol_with_indent[from.to_owned()..to.to_owned()];
-
-                        if *from == 0 && *to == 0 {
-                            symbol_name_with_context.push_str("anonymous");
-                        } else {
-                            symbol_name_with_context.push_str(node_symbol);
-                        }
-
-                        let indent_size = indentation_context.len();
-                        let comment_line: String = format!(
-                            "{} {}",
-                            indent_comment_pool[indent_size].clone(),
-                            symbol_name_with_context
-                        );
-
-                        if latest_comment_line != comment_line {
-                            writer_queue.push_back(comment_line);
-                        }
-                        if !pending_queue.is_empty() {
-                            while !pending_queue.is_empty() {
-                                if let Some(queued_line) = pending_queue.pop_front() {
-                                    writer_queue.push_back(queued_line.to_owned());
-                                }
-                            }
-                        }
-                        writer_queue.push_back(line.to_owned());
-                        pop_node = true;
-                    }
+        Ok(annotated)
+    }
 
-                    if !indentation_context.is_empty() {
-                        if let Some((current_context, _)) = indentation_context.back() {
-                            if cursor_position.row >= current_context.end_position().row {
-                                indentation_context.pop_back();
-                            }
-                        }
-                    }
+    /// Collects every symbol which gets a TODO comment, in order of appearance
+    pub fn annotations(&self) -> Result<Vec<Annotation>> {
+        let (annotations, _, _) = self.reconcile()?;
 
-                    if nested_traversable_symbols.contains(&node_type) {
-                        let (_, from, to) = current_node.identifier_range();
+        Ok(annotations)
+    }
 
-                        let symbol: String;
-                        if from == 0 && to == 0 {
-                            symbol = "anonymous".to_string();
-                        } else {
-                            symbol = line[from.to_owned()..to.to_owned()].to_string();
-                        }
+    /// Returns the source code without the TODO comments generated by balpan
+    ///
+    /// TODO comments are told apart from hand-written comments which look like them by
+    /// the same rules as `analyze`, so stripping an analyzed file gives back the original file.
+    /// Every other line is kept as it is, including its line ending and a BOM.
+    pub fn strip(&self) -> Result<String> {
+        let (bom, source_code) = split_bom(&self.source_code);
+        let marker = Marker::from_language(&self.language)?;
+        let (_, is_generated, _) = self.match_todo_comments(source_code, &marker)?;
+
+        let lines = source_code
+            .split_inclusive('\n')
+            .zip(is_generated)
+            .filter(|(_, is_generated)| !is_generated)
+            .map(|(line, _)| line);
+
+        Ok(std::iter::once(bom).chain(lines).collect())
+    }
 
-                        indentation_context.push_back((*current_node, symbol));
-                        pop_node = true;
-                    }
+    fn reconcile(&self) -> Result<(Vec<Annotation>, VecDeque<String>, String)> {
+        let (bom, source_code) = split_bom(&self.source_code);
+        let lines: Vec<&str> = source_code.lines().collect();
+        let lines_with_ending: Vec<&str> = source_code.split_inclusive('\n').collect();
+        let line_ending = dominant_line_ending(&lines_with_ending);
+        let marker = Marker::from_language(&self.language)?;
+        let (mut annotations, is_generated, is_doc_comment) =
+            self.match_todo_comments(source_code, &marker)?;
+        let separates_doc_comments = self.language.separates_doc_comments();
 
-                    if cursor_position == current_node.end_position() {
-                        pop_node = true;
-                    }
+        let mut writer_queue = VecDeque::new();
+        let mut annotated = String::from(bom);
+        let mut pending_annotations = annotations.iter_mut().peekable();
 
-                    if pop_node {
-                        nodes_queue.pop_front();
-                    }
-                }
-                _ => {
-                    if !indentation_context.is_empty() {
-                        if let Some((current_context, _)) = indentation_context.back() {
-                            if cursor_position.row >= current_context.end_position().row {
-                                indentation_context.pop_back();
-                            }
-                        }
-                    }
+        for (row, line) in lines.iter().enumerate() {
+            let mut has_inserted = false;
 
-                    if line == latest_comment_line && latest_comment_line_index == line_idx - 1 {
-                        continue;
-                    }
+            while let Some(annotation) =
+                pending_annotations.next_if(|annotation| annotation.target_line == row)
+            {
+                let indentation = self.indentation_of(annotation, &lines);
+                let comment_line = annotation.comment_line(&indentation, &marker);
 
-                    let indentation_level = indentation_context.len();
-                    if line.starts_with(&indent_comment_pool[indentation_level]) {
-                        latest_comment_line = line;
-                        latest_comment_line_index = line_idx;
-                    }
-                    writer_queue.push_back(line.to_owned());
-                }
+                annotated.push_str(&comment_line);
+                annotated.push_str(line_ending);
+
+                annotation.line = writer_queue.len();
+                writer_queue.push_back(comment_line);
+                has_inserted = true;
+            }
+
+            if has_inserted && separates_doc_comments && is_doc_comment[row] {
+                annotated.push_str(line_ending);
+                writer_queue.push_back(String::new());
+            }
+
+            if !is_generated[row] {
+                annotated.push_str(lines_with_ending[row]);
+                writer_queue.push_back(line.to_string());
             }
         }
 
-        writer_queue.to_owned()
+        Ok((annotations, writer_queue, annotated))
     }
 
-    /// This methods collects treesitter nodes with BFS
+    /// Collects the symbols which get TODO comments, and matches them with the TODO comments
+    /// which are already in the source code
     ///
-    /// All of tree sitter nodes are ordered by non decreasing order
-    fn get_scannable_nodes(&self, tree: &'tree Tree) -> Vec<(Node<'tree>, (usize, usize, usize))> {
-        let mut deq: VecDeque<Node<'tree>> = VecDeque::new();
-        let scannable_node_types = self.language.scannable_node_types();
-        let nested_traversable_symbols = self.language.nested_traversable_symbols();
-        let mut result = Vec::new();
-        deq.push_back(tree.root_node());
+    /// Returns the annotations, along with the lines of the TODO comments generated by balpan
+    /// and the lines of doc comments. The generated lines are the TODO comments taken over
+    /// by a symbol, the blank lines which separate them from doc comments, and the TODO comments
+    /// of deleted symbols. Those are replaced or removed by `analyze` and removed by `strip`.
+    fn match_todo_comments(
+        &self,
+        source_code: &str,
+        marker: &Marker,
+    ) -> Result<(Vec<Annotation>, Vec<bool>, Vec<bool>)> {
+        let tree = self.get_syntax_tree(source_code)?;
+        let lines: Vec<&str> = source_code.lines().collect();
+
+        let existing_comments: Vec<Option<(&str, &str)>> =
+            lines.iter().map(|line| marker.parse(line)).collect();
+
+        let mut annotations = Vec::new();
+        let mut context = Vec::new();
+        let definitions = self.get_symbol_definitions(&tree, source_code);
+        self.collect_annotations(
+            tree.root_node(),
+            &lines,
+            definitions.as_ref(),
+            &mut context,
+            0,
+            &mut annotations,
+        )?;
+        annotations.sort_by_key(|annotation| annotation.target_line);
+        mark_test_code(&mut annotations);
+
+        // symbols skipped below still enclose the TODO comments of their members
+        let scopes: Vec<(Range, String)> = annotations
+            .iter()
+            .map(|annotation| (annotation.range, annotation.symbol_path.join(" > ")))
+            .collect();
+
+        let test_code = self.language.test_code();
+        if test_code == TestCode::Skip {
+            annotations.retain(|annotation| !annotation.is_test);
+        }
 
-        while !deq.is_empty() {
-            if let Some(node) = deq.pop_front() {
-                let node_type = node.kind();
+        let symbol_paths: HashSet<String> = annotations
+            .iter()
+            .map(|annotation| annotation.symbol_path.join(" > "))
+            .collect();
+
+        let is_doc_comment = self.doc_comment_lines(&lines, &existing_comments);
+        let separates_doc_comments = self.language.separates_doc_comments();
+
+        // Each symbol takes over the TODO comments of its own path stacked right above it,
+        // or the nearest one of a renamed symbol, and keeps the state of the nearest one.
+        // The TODO comment goes above the doc comment of the symbol, so that the doc comment
+        // stays attached to the symbol, unless it is a doc comment itself.
+        let mut is_claimed = vec![false; lines.len()];
+        // old paths of renamed symbols, e.g. `Post` to `Article` after `class Post` is renamed
+        let mut renamed_scopes: HashMap<String, String> = HashMap::new();
+        for annotation in annotations.iter_mut() {
+            annotation.state = match test_code {
+                TestCode::Mark if annotation.is_test => marker.test_state().to_string(),
+                _ => marker.todo_state().to_string(),
+            };
+            let symbol_path = annotation.symbol_path.join(" > ");
+            let symbol_scope = symbol_path
+                .rsplit_once(" > ")
+                .map_or("", |(scope, _)| scope);
+            let mut row = annotation.target_line;
+            let mut has_claimed = false;
+            let mut separator = None;
+
+            while row > 0 {
+                row -= 1;
+
+                if is_doc_comment[row] && separator.is_none() {
+                    annotation.target_line = row;
+                    continue;
+                }
 
-                if scannable_node_types.contains(&node_type) {
-                    let identifier_range = node.identifier_range();
-                    result.push((node.to_owned(), identifier_range));
+                // e.g. the blank line between `// [TODO] Engine` and the doc comment of
+                // `type Engine struct` in go
+                let is_separator = separates_doc_comments
+                    && separator.is_none()
+                    && row + 1 == annotation.target_line
+                    && is_doc_comment[annotation.target_line]
+                    && lines[row].trim().is_empty();
+
+                if is_separator {
+                    separator = Some(row);
+                    continue;
                 }
 
-                if !nested_traversable_symbols.contains(&node_type)
-                    && node_type != self.language.top_level_node_type()
-                {
+                let Some((state, path)) = existing_comments[row] else {
+                    break;
+                };
+                let path = path.trim();
+
+                let is_renamed = !has_claimed
+                    && !symbol_paths.contains(path)
+                    && is_generated_path(path, symbol_scope, &renamed_scopes);
+
+                if is_claimed[row] || !(path == symbol_path || is_renamed) {
                     continue;
                 }
-                deq = self.enqueue_child_nodes(deq, &node);
+
+                is_claimed[row] = true;
+                if let Some(separator) = separator {
+                    is_claimed[separator] = true;
+                }
+                if is_renamed {
+                    renamed_scopes.insert(path.to_string(), symbol_path.clone());
+                }
+                if !has_claimed {
+                    has_claimed = true;
+                    annotation.state = state.to_string();
+                }
             }
         }
 
-        result.sort_by(|(u, _), (v, _)| u.start_position().row.cmp(&v.start_position().row));
+        // TODO comments of deleted symbols, which are left in the scope their path says
+        let is_generated: Vec<bool> = existing_comments
+            .iter()
+            .enumerate()
+            .map(|(row, existing_comment)| match existing_comment {
+                _ if is_claimed[row] => true,
+                Some((_, path)) => {
+                    let path = path.trim();
+
+                    !symbol_paths.contains(path)
+                        && is_generated_path(path, enclosing_scope(&scopes, row), &renamed_scopes)
+                }
+                None => false,
+            })
+            .collect();
 
-        result.to_owned()
+        Ok((annotations, is_generated, is_doc_comment))
     }
 
-    fn enqueue_child_nodes(
+    /// Marks the lines of doc comments, e.g. `/// Returns foo` or every line of `/** ... */`
+    ///
+    /// No line is marked if TODO comments are written with a doc comment token themselves,
+    /// since a TODO comment above a doc comment would become its first line, e.g. the summary
+    /// of the symbol in rustdoc. Such TODO comments stay right above the symbol instead,
+    /// unless the language separates them from doc comments with a blank line.
+    fn doc_comment_lines(
         &self,
-        mut deq: VecDeque<Node<'tree>>,
-        node: &Node<'tree>,
-    ) -> VecDeque<Node<'tree>> {
-        let mut cursor = node.walk();
-        let scannable_node_types = self.language.scannable_node_types();
-        let node_type = node.kind();
-
-        if self.language == Language::Ruby {
-            if node_type == self.language.top_level_node_type() {
-                for child_node in node.children(&mut cursor) {
-                    if scannable_node_types.contains(&child_node.kind()) {
-                        deq.push_back(child_node);
+        lines: &[&str],
+        existing_comments: &[Option<(&str, &str)>],
+    ) -> Vec<bool> {
+        let doc_comment_tokens = self.language.doc_comment_tokens();
+        let mut is_in_block = false;
+
+        let marker_token = self
+            .language
+            .marker_configuration()
+            .comment_token(&self.language);
+
+        let is_marker_doc_comment =
+            marker_token.is_some_and(|token| doc_comment_tokens.contains(&token));
+
+        if is_marker_doc_comment && !self.language.separates_doc_comments() {
+            return vec![false; lines.len()];
+        }
+
+        lines
+            .iter()
+            .zip(existing_comments)
+            .map(|(line, existing_comment)| {
+                let line = line.trim_start();
+
+                if is_in_block {
+                    is_in_block = !line.contains("*/");
+                    return true;
+                }
+
+                if existing_comment.is_some() {
+                    return false;
+                }
+
+                match doc_comment_tokens
+                    .iter()
+                    .find(|token| line.starts_with(*token))
+                {
+                    Some(token) if token.starts_with("/*") => {
+                        is_in_block = !line[token.len()..].contains("*/");
+                        true
                     }
+                    Some(_) => true,
+                    None => false,
                 }
-                return deq;
+            })
+            .collect()
+    }
+
+    /// Walks through the scannable child nodes of `node` and collects annotations
+    ///
+    /// Decorators are not annotated by themselves. Instead, the TODO comment of the
+    /// decorated symbol is placed above the first decorator.
+    ///
+    /// Calls which define tests, e.g. `describe("foo", () => {})` in javascript, are
+    /// annotated even if their node type is not commentable, see `TestPatterns`.
+    ///
+    /// A namespace declared without a block (e.g. `namespace Foo;`) is added to the
+    /// symbol path of the following siblings, without indenting their TODO comments.
+    ///
+    /// The symbols are the nodes whose type is commentable and not ignorable. If the language
+    /// has a `symbols.scm` query, only the definitions found by the query are symbols,
+    /// and they are named by the query.
+    ///
+    /// Fails if the name of a symbol can't be resolved from its node.
+    fn collect_annotations(
+        &self,
+        node: Node<'tree>,
+        lines: &[&str],
+        definitions: Option<&SymbolDefinitions>,
+        context: &mut Vec<String>,
+        depth: usize,
+        annotations: &mut Vec<Annotation>,
+    ) -> Result<()> {
+        let ignorable_node_types = self.language.ignorable_node_types();
+        let commentable_node_types = self.language.commentable_node_types();
+        let decorator_node_type = self.language.decorator_node_type();
+        let transparent_node_types = self.language.transparent_node_types();
+        let placement = self
+            .marker_placement
+            .unwrap_or_else(|| self.language.marker_placement());
+        let test_patterns = self.language.test_patterns();
+
+        let enclosing_scope_len = context.len();
+        let mut decorators: Vec<Node> = vec![];
+        // typescript specific code: the symbol path of the overload signatures annotated last
+        let mut overloaded_symbol_path: Option<Vec<String>> = None;
+
+        for child in self.child_nodes(&node) {
+            // e.g. `template <typename T>` in c++ is part of the templated symbol,
+            // so the TODO comment is placed above it
+            let declaration = wrapped_declaration(&child).unwrap_or(child);
+
+            let call_target;
+            let node_type = match declaration.call_target_range() {
+                // elixir specific code: `defmodule Foo do` and `def foo do` are macro calls
+                Some(range) if self.language == Language::Elixir => {
+                    call_target = text_of(range, lines);
+                    call_target.as_str()
+                }
+                _ => declaration.kind(),
+            };
+
+            // comments between decorators and the decorated symbol
+            if child.is_extra() {
+                continue;
             }
-        } else {
-            for child_node in node.children(&mut cursor) {
-                if scannable_node_types.contains(&child_node.kind()) {
-                    deq.push_back(child_node);
+
+            if node_type == decorator_node_type {
+                decorators.push(child);
+                continue;
+            }
+
+            if transparent_node_types.contains(&node_type) {
+                self.collect_annotations(child, lines, definitions, context, depth, annotations)?;
+                continue;
+            }
+
+            // `mod foo;` in rust and `struct foo;` in c declare a symbol defined elsewhere
+            let is_forward_declaration = matches!(
+                node_type,
+                "mod_item" | "struct_specifier" | "union_specifier" | "enum_specifier"
+            ) && declaration.child_by_field_name("body").is_none();
+
+            // e.g. `const foo = 1` and `export const foo = 1` in typescript,
+            // unlike `const foo = async () => {}`
+            let is_plain_variable = exported_declaration(&declaration)
+                .filter(|variable| {
+                    matches!(
+                        variable.kind(),
+                        "lexical_declaration" | "variable_declaration"
+                    )
+                })
+                .is_some_and(|variable| !declares_function(&variable));
+
+            // e.g. `export { foo }`, `export default foo` and `export * from "./foo"`
+            let exports_declaration = exported_declaration(&declaration).is_some();
+
+            let is_test_call = !test_patterns.calls.is_empty()
+                && called_function_name(&declaration, lines)
+                    .is_some_and(|function| test_patterns.calls.contains(&function));
+
+            let is_commentable = is_test_call
+                || (definitions.is_none_or(|definitions| definitions.contains_key(&child.id()))
+                    && !ignorable_node_types.contains(&node_type)
+                    && commentable_node_types.contains(&node_type)
+                    && !is_forward_declaration
+                    && !is_plain_variable
+                    && exports_declaration);
+
+            if !is_commentable {
+                decorators.clear();
+                continue;
+            }
+
+            // e.g. `namespace Foo;` in c# and php
+            let is_file_scoped_namespace = node_type == "file_scoped_namespace_declaration"
+                || (node_type == "namespace_definition"
+                    && self.language == Language::Php
+                    && declaration.child_by_field_name("body").is_none());
+
+            if is_file_scoped_namespace {
+                context.truncate(enclosing_scope_len);
+            }
+
+            let symbol = match definitions.and_then(|definitions| definitions.get(&child.id())) {
+                Some(Some(name)) => name.clone(),
+                Some(None) => "anonymous".to_string(),
+                None if is_test_call => {
+                    test_call_name(&declaration, lines).unwrap_or_else(|| "anonymous".to_string())
                 }
+                None => symbol_name(&declaration, lines)?,
+            };
+            let mut symbol_path = context.clone();
+
+            // go specific code: methods belong to the type of their receiver
+            if let Some(receiver_type_range) = declaration.receiver_type_range() {
+                symbol_path.push(text_of(receiver_type_range, lines));
+            }
+
+            // c++ specific code: `void Foo::bar() {}` belongs to `Foo`
+            for qualifier_range in declaration.qualifier_ranges() {
+                symbol_path.push(text_of(qualifier_range, lines));
+            }
+
+            symbol_path.push(symbol.clone());
+
+            let decorator_line = decorators
+                .first()
+                .map(|decorator| decorator.start_position().row);
+            let target_line = target_line(&child, decorator_line, placement);
+            let is_test = is_test_call
+                || is_test_code(&declaration, &symbol, &decorators, test_patterns, lines);
+            decorators.clear();
+
+            // php specific code: a TODO comment above a line starting with `<?php` or html
+            // would be printed as a text, so only the members of the symbol are annotated
+            let is_inline_of_template =
+                self.language == Language::Php && !starts_line(&child, lines);
+
+            // typescript specific code: the overload signatures of a function and its
+            // implementation are annotated once, above the first signature
+            let is_overload = overloaded_symbol_path.as_ref() == Some(&symbol_path);
+            let is_signature = exported_declaration(&declaration).is_some_and(|declaration| {
+                matches!(
+                    declaration.kind(),
+                    "function_signature" | "method_signature"
+                )
+            });
+            overloaded_symbol_path = is_signature.then(|| symbol_path.clone());
+
+            if !is_inline_of_template && !is_overload {
+                annotations.push(Annotation {
+                    symbol_path,
+                    kind: node_type.to_string(),
+                    range: Range::from_node(child),
+                    target_line,
+                    line: 0,
+                    depth,
+                    state: String::new(),
+                    is_test,
+                });
+            }
+
+            // the members of a test call, e.g. the `it("...", () => {})` calls in the callback
+            // of `describe("...", () => {})`, are in its block rather than in the call itself
+            let members = if is_test_call {
+                test_call_body(&declaration)
+                    .filter(|_| self.language.max_depth().is_none_or(|max| depth < max))
+            } else if self.language.is_traversable(node_type, depth) {
+                Some(declaration)
+            } else {
+                None
+            };
+
+            if is_file_scoped_namespace {
+                context.push(symbol);
+            } else if let Some(members) = members {
+                context.push(symbol);
+                self.collect_annotations(
+                    members,
+                    lines,
+                    definitions,
+                    context,
+                    depth + 1,
+                    annotations,
+                )?;
+                context.pop();
             }
         }
 
-        cursor.reset(*node);
+        context.truncate(enclosing_scope_len);
 
-        if let Some(body) = node.child_by_field_name("body") {
-            let mut body_cursor = body.walk();
-            for child_node in body.children(&mut body_cursor) {
-                if scannable_node_types.contains(&child_node.kind()) {
-                    deq.push_back(child_node);
+        Ok(())
+    }
+
+    /// Lists the named child nodes of `node`, followed by the child nodes of its body
+    ///
+    /// The members of a decorated definition, e.g. `@dataclass class Foo:` in python,
+    /// are the ones of the definition.
+    fn child_nodes(&self, node: &Node<'tree>) -> Vec<Node<'tree>> {
+        if let Some(definition) = node.child_by_field_name("definition") {
+            return self.child_nodes(&definition);
+        }
+
+        let mut result = Vec::new();
+        let body = node.child_by_field_name("body");
+
+        // c++ specific code: `extern "C" int foo() {}` has a single declaration as its body
+        if let Some(body) = body.filter(|body| {
+            node.kind() == "linkage_specification" && body.kind() != "declaration_list"
+        }) {
+            return vec![body];
+        }
+
+        let is_top_level = node.kind() == self.language.top_level_node_type();
+
+        if self.language != Language::Ruby || is_top_level {
+            let mut cursor = node.walk();
+            for child_node in node.named_children(&mut cursor) {
+                if Some(child_node) != body {
+                    result.push(child_node);
                 }
             }
         }
 
-        deq
+        if let Some(body) = body {
+            let mut body_cursor = body.walk();
+            for child_node in body.named_children(&mut body_cursor) {
+                result.push(child_node);
+            }
+        }
+
+        result
+    }
+}
+
+/// Splits a UTF-8 BOM off the source code, so that it doesn't shift the columns of the first line
+pub fn split_bom(source_code: &str) -> (&str, &str) {
+    match source_code.strip_prefix(BOM) {
+        Some(rest) => (BOM, rest),
+        None => ("", source_code),
+    }
+}
+
+/// Checks whether balpan could have generated a TODO comment of the path in the given scope,
+/// unlike a hand-written comment which only looks like one, e.g. `# [TODO] drop py2 support`
+///
+/// The scope of the path must be the given scope, or its old path if it is renamed.
+/// A path at the top level must be a single name.
+fn is_generated_path(path: &str, scope: &str, renamed_scopes: &HashMap<String, String>) -> bool {
+    match path.rsplit_once(" > ") {
+        Some((path_scope, _)) => {
+            path_scope == scope
+                || renamed_scopes
+                    .get(path_scope)
+                    .is_some_and(|renamed| renamed == scope)
+        }
+        None => scope.is_empty() && !path.is_empty() && !path.contains(char::is_whitespace),
+    }
+}
+
+/// Path of the innermost symbol whose range contains the row, or `""` at the top level
+fn enclosing_scope(scopes: &[(Range, String)], row: usize) -> &str {
+    scopes
+        .iter()
+        .filter(|(range, _)| range.start_point.row < row && row <= range.end_point.row)
+        .max_by_key(|(range, _)| range.start_byte)
+        .map_or("", |(_, path)| path.as_str())
+}
+
+/// `\r\n` if more lines end with it than with `\n` alone, otherwise `\n`
+fn dominant_line_ending(lines_with_ending: &[&str]) -> &'static str {
+    let crlf_count = lines_with_ending
+        .iter()
+        .filter(|line| line.ends_with("\r\n"))
+        .count();
+    let lf_count = lines_with_ending
+        .iter()
+        .filter(|line| line.ends_with('\n'))
+        .count()
+        - crlf_count;
+
+    if crlf_count > lf_count {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Marks every annotation inside the range of a test code annotation as test code,
+/// e.g. the functions of `#[cfg(test)] mod tests` in rust
+fn mark_test_code(annotations: &mut [Annotation]) {
+    let test_ranges: Vec<Range> = annotations
+        .iter()
+        .filter(|annotation| annotation.is_test)
+        .map(|annotation| annotation.range)
+        .collect();
+
+    for annotation in annotations.iter_mut() {
+        annotation.is_test |= test_ranges.iter().any(|range| {
+            range.start_byte <= annotation.range.start_byte
+                && annotation.range.end_byte <= range.end_byte
+        });
+    }
+}
+
+/// Checks whether a symbol is test code by its decorators, its name or its superclasses
+fn is_test_code(
+    node: &Node,
+    symbol: &str,
+    decorators: &[Node],
+    patterns: &TestPatterns,
+    lines: &[&str],
+) -> bool {
+    let matches_any =
+        |regexes: &[Regex], text: &str| regexes.iter().any(|regex| regex.is_match(text));
+    let declaration = node.child_by_field_name("definition").unwrap_or(*node);
+
+    decorators
+        .iter()
+        .any(|decorator| matches_any(&patterns.attributes, node_text(decorator, lines).trim()))
+        || matches_any(&patterns.names, symbol)
+        || superclasses(&declaration)
+            .iter()
+            .any(|superclass| matches_any(&patterns.superclasses, &node_text(superclass, lines)))
+}
+
+/// Lists the superclasses of a class, e.g. `unittest.TestCase` of `class Foo(unittest.TestCase):`
+fn superclasses<'tree>(node: &Node<'tree>) -> Vec<Node<'tree>> {
+    // `superclasses` in python, `superclass` in ruby
+    let Some(superclasses) = node
+        .child_by_field_name("superclasses")
+        .or_else(|| node.child_by_field_name("superclass"))
+    else {
+        return vec![];
+    };
+
+    let mut cursor = superclasses.walk();
+    let result = superclasses.named_children(&mut cursor).collect();
+
+    result
+}
+
+/// Name of the function called by a statement, e.g. `describe` of `describe("foo", () => {})`
+/// in javascript or of `describe Foo do ... end` in ruby
+///
+/// `describe.each(table)(...)` and `it.only(...)` are calls of `describe` and `it` as well.
+/// A call which is only a part of a statement, e.g. an argument, is not matched.
+fn called_function_name(node: &Node, lines: &[&str]) -> Option<String> {
+    let mut current = match node.kind() {
+        "expression_statement" => node.named_child(0)?,
+        // ruby specific code: a call is a statement by itself
+        "call" => *node,
+        _ => return None,
+    };
+
+    loop {
+        current = match current.kind() {
+            "call_expression" => current.child_by_field_name("function")?,
+            "member_expression" => current.child_by_field_name("object")?,
+            "call" => {
+                let method = current.child_by_field_name("method")?;
+                return Some(node_text(&method, lines));
+            }
+            "identifier" => return Some(node_text(&current, lines)),
+            _ => return None,
+        };
+    }
+}
+
+/// Names a call which defines a test after its first argument,
+/// e.g. `parses numbers` of `it("parses numbers", () => {})`
+fn test_call_name(node: &Node, lines: &[&str]) -> Option<String> {
+    let call = match node.kind() {
+        "expression_statement" => node.named_child(0)?,
+        _ => *node,
+    };
+    let arguments = call.child_by_field_name("arguments")?;
+    let first_argument = arguments.named_child(0)?;
+
+    let text = node_text(&first_argument, lines);
+    Some(text.trim_matches(['"', '\'', '`']).to_string())
+}
+
+/// Block which holds the tests defined by a test call, e.g. the body of the callback
+/// of `describe("foo", () => {})` in javascript or `do ... end` of `describe Foo do` in ruby
+fn test_call_body<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    let call = match node.kind() {
+        "expression_statement" => node.named_child(0)?,
+        _ => *node,
+    };
+
+    if let Some(block) = call.child_by_field_name("block") {
+        return Some(block);
+    }
+
+    let arguments = call.child_by_field_name("arguments")?;
+    let mut cursor = arguments.walk();
+    let callback = arguments
+        .named_children(&mut cursor)
+        .filter(|argument| {
+            matches!(
+                argument.kind(),
+                "arrow_function" | "function" | "function_expression"
+            )
+        })
+        .last()?;
+
+    callback
+        .child_by_field_name("body")
+        .filter(|body| body.kind() == "statement_block")
+}
+
+/// Row which the TODO comment of `node` is placed above
+fn target_line(node: &Node, decorator_line: Option<usize>, placement: MarkerPlacement) -> usize {
+    // e.g. `decorated_definition` in python wraps the decorators along with the definition
+    let declaration = node
+        .child_by_field_name("definition")
+        .or_else(|| wrapped_declaration(node))
+        .unwrap_or(*node);
+    let declaration_line = declaration.start_position().row;
+
+    match placement {
+        MarkerPlacement::AboveDecorators => decorator_line.unwrap_or(node.start_position().row),
+        MarkerPlacement::AboveDeclaration => declaration_line,
+        MarkerPlacement::StartOfBody => first_statement_line(&declaration)
+            .filter(|row| *row > declaration_line)
+            .unwrap_or(declaration_line),
+    }
+}
+
+/// The declaration exported by `export` in javascript, or the node itself if it is not exported
+fn exported_declaration<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    match node.kind() {
+        "export_statement" => node.child_by_field_name("declaration"),
+        _ => Some(*node),
+    }
+}
+
+/// Checks whether a variable declaration declares a function,
+/// e.g. `const foo = async () => {}` in javascript
+fn declares_function(node: &Node) -> bool {
+    node.named_child(0)
+        .filter(|declarator| declarator.kind() == "variable_declarator")
+        .and_then(|declarator| declarator.child_by_field_name("value"))
+        .is_some_and(|value| {
+            matches!(
+                value.kind(),
+                "arrow_function" | "function" | "function_expression" | "generator_function"
+            )
+        })
+}
+
+/// Finds the row of the first statement in the body of `node`, skipping its docstring
+fn first_statement_line(node: &Node) -> Option<usize> {
+    let body = node.child_by_field_name("body")?;
+    let mut cursor = body.walk();
+
+    let mut statements = body
+        .named_children(&mut cursor)
+        .filter(|child| !child.is_extra())
+        .peekable();
+
+    statements.next_if(is_docstring);
+    statements
+        .next()
+        .map(|statement| statement.start_position().row)
+}
+
+fn is_docstring(node: &Node) -> bool {
+    node.kind() == "expression_statement"
+        && node
+            .named_child(0)
+            .is_some_and(|expression| expression.kind() == "string")
+}
+
+fn symbol_name(node: &Node, lines: &[&str]) -> Result<String> {
+    let (row, from, to) = node.identifier_range()?;
+
+    if from == 0 && to == 0 {
+        return Ok("anonymous".to_string());
+    }
+
+    Ok(text_of((row, from, to), lines))
+}
+
+/// Checks whether nothing but whitespaces comes before the node in its line
+fn starts_line(node: &Node, lines: &[&str]) -> bool {
+    let Point { row, column } = node.start_position();
+
+    match lines.get(row).and_then(|line| line.get(..column)) {
+        Some(prefix) => prefix.trim().is_empty(),
+        None => true,
+    }
+}
+
+/// Returns the text of a node, whose lines are joined with `\n`
+fn node_text(node: &Node, lines: &[&str]) -> String {
+    let (start, end) = (node.start_position(), node.end_position());
+
+    if start.row == end.row {
+        return text_of((start.row, start.column, end.column), lines);
+    }
+
+    let line = |row: usize| lines.get(row).copied().unwrap_or("");
+    let mut text = line(start.row)
+        .get(start.column..)
+        .unwrap_or("")
+        .to_string();
+
+    for row in start.row + 1..end.row {
+        text.push('\n');
+        text.push_str(line(row));
+    }
+
+    text.push('\n');
+    text.push_str(line(end.row).get(..end.column).unwrap_or(""));
+    text
+}
+
+/// Returns the text in the given range, or `UNRESOLVED_SYMBOL_NAME` if it is out of the lines
+fn text_of((row, from, to): (usize, usize, usize), lines: &[&str]) -> String {
+    lines
+        .get(row)
+        .and_then(|line| line.get(from..to))
+        .unwrap_or(UNRESOLVED_SYMBOL_NAME)
+        .to_string()
+}
+
+impl Annotation {
+    fn comment_line(&self, indentation: &str, marker: &Marker) -> String {
+        format!(
+            "{}{}",
+            indentation,
+            marker.render(&self.state, &self.symbol_path.join(" > "))
+        )
     }
 }
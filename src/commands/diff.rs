@@ -0,0 +1,321 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::suggest_subcommand;
+
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Equal { old: usize, new: usize },
+    Insert { new: usize },
+    Delete { old: usize },
+}
+
+/// Computes a line based edit script between `old` and `new`
+///
+/// This is an implementation of Myers' O(ND) difference algorithm.
+/// Only the diagonals reachable on each step are remembered, so the memory usage is
+/// proportional to D^2 rather than to the size of the files. This fits well for balpan,
+/// because analyzing a file only inserts a handful of lines into it.
+pub fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+
+    let mut v = vec![0_isize; 2 * max + 3];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max as isize {
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[index] = x;
+
+            if x >= n && y >= m {
+                trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+                break 'search;
+            }
+        }
+
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+    }
+
+    backtrack(&trace, n, m)
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+
+    for d in (0..trace.len() as isize).rev() {
+        let k = x - y;
+
+        let (prev_k, prev_x) = if d == 0 {
+            (0, 0)
+        } else {
+            // `trace[d - 1]` only holds the diagonals from -(d - 1) to d - 1
+            let prev = &trace[(d - 1) as usize];
+            let get = |k: isize| prev[(k + d - 1) as usize];
+
+            let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+
+            (prev_k, get(prev_k))
+        };
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal {
+                old: x as usize,
+                new: y as usize,
+            });
+        }
+
+        if d > 0 {
+            if x == prev_x {
+                edits.push(Edit::Insert {
+                    new: prev_y as usize,
+                });
+            } else {
+                edits.push(Edit::Delete {
+                    old: prev_x as usize,
+                });
+            }
+        }
+
+        x = prev_x;
+        y = prev_y;
+    }
+
+    edits.reverse();
+    edits
+}
+
+/// Renders a unified diff between two versions of a file
+///
+/// Returns an empty string when both versions are the same.
+pub fn unified_diff(file_name: &str, original: &str, modified: &str) -> String {
+    let old: Vec<&str> = original.lines().collect();
+    let new: Vec<&str> = modified.lines().collect();
+    let edits = diff_lines(&old, &new);
+
+    let mut result = String::new();
+
+    for hunk in group_hunks(&edits) {
+        if result.is_empty() {
+            result.push_str(&format!("--- a/{}\n+++ b/{}\n", file_name, file_name));
+        }
+
+        let (old_start, old_len, new_start, new_len) = hunk_header(hunk);
+        result.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_len, new_start, new_len
+        ));
+
+        for edit in hunk {
+            match edit {
+                Edit::Equal { old: i, .. } => result.push_str(&format!(" {}\n", old[*i])),
+                Edit::Delete { old: i } => result.push_str(&format!("-{}\n", old[*i])),
+                Edit::Insert { new: j } => result.push_str(&format!("+{}\n", new[*j])),
+            }
+        }
+    }
+
+    result
+}
+
+/// Splits an edit script into hunks surrounded by `CONTEXT_LINES` lines of context
+fn group_hunks(edits: &[Edit]) -> Vec<&[Edit]> {
+    let mut hunks = Vec::new();
+    let changes: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Equal { .. }))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut iter = changes.into_iter().peekable();
+
+    while let Some(first) = iter.next() {
+        let start = first.saturating_sub(CONTEXT_LINES);
+        let mut last = first;
+
+        while let Some(&next) = iter.peek() {
+            if next - last > 2 * CONTEXT_LINES {
+                break;
+            }
+            last = next;
+            iter.next();
+        }
+
+        let end = usize::min(last + CONTEXT_LINES + 1, edits.len());
+        hunks.push(&edits[start..end]);
+    }
+
+    hunks
+}
+
+fn hunk_header(hunk: &[Edit]) -> (usize, usize, usize, usize) {
+    let mut old_start = None;
+    let mut new_start = None;
+    let mut old_len = 0;
+    let mut new_len = 0;
+
+    for edit in hunk {
+        match *edit {
+            Edit::Equal { old, new } => {
+                old_start.get_or_insert(old);
+                new_start.get_or_insert(new);
+                old_len += 1;
+                new_len += 1;
+            }
+            Edit::Delete { old } => {
+                old_start.get_or_insert(old);
+                old_len += 1;
+            }
+            Edit::Insert { new } => {
+                new_start.get_or_insert(new);
+                new_len += 1;
+            }
+        }
+    }
+
+    // unified diff uses 1-based line numbers, and the line *before* an empty range
+    let to_header = |start: Option<usize>, len: usize| match (start, len) {
+        (Some(start), len) if len > 0 => start + 1,
+        (Some(start), _) => start,
+        (None, _) => 0,
+    };
+
+    (
+        to_header(old_start, old_len),
+        old_len,
+        to_header(new_start, new_len),
+        new_len,
+    )
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct DiffReport {
+    pub files: Vec<DiffFile>,
+    /// Directory which the file names are relative to, e.g. the workdir of the repository
+    #[serde(skip)]
+    root: Option<PathBuf>,
+}
+
+/// Changes of a file in the dry run report
+///
+/// A TODO comment whose symbol path or state is updated is listed as removed
+/// from its old line and inserted into its new line.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffFile {
+    pub name: String,
+    /// TODO comments which would be inserted, numbered by the lines of the modified file
+    pub markers: Vec<MarkerLine>,
+    /// TODO comments which would be removed, numbered by the lines of the original file
+    pub removed_markers: Vec<MarkerLine>,
+    #[serde(skip)]
+    diff: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarkerLine {
+    pub line: usize,
+    pub content: String,
+}
+
+impl DiffReport {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a report whose files are named relative to `root`,
+    /// so that the diff can be applied with `git apply` or `patch -p1` from there
+    pub fn with_root(root: &Path) -> Self {
+        DiffReport {
+            root: Some(root.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    /// Records the changes `balpan` would make to a file, without touching it
+    pub fn add_file(&mut self, path: &Path, original: &str, modified: &str) {
+        let relative_path = self
+            .root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        let name = relative_path.display().to_string();
+        let diff = unified_diff(&name, original, modified);
+
+        if diff.is_empty() {
+            return;
+        }
+
+        let new: Vec<&str> = modified.lines().collect();
+        let old: Vec<&str> = original.lines().collect();
+        let mut markers = vec![];
+        let mut removed_markers = vec![];
+
+        for edit in diff_lines(&old, &new) {
+            match edit {
+                Edit::Insert { new: j } => markers.push(MarkerLine {
+                    line: j + 1,
+                    content: new[j].to_string(),
+                }),
+                Edit::Delete { old: i } => removed_markers.push(MarkerLine {
+                    line: i + 1,
+                    content: old[i].to_string(),
+                }),
+                Edit::Equal { .. } => {}
+            }
+        }
+
+        self.files.push(DiffFile {
+            name,
+            markers,
+            removed_markers,
+            diff,
+        });
+    }
+
+    pub fn report_formatting(&self, format: Option<String>) -> String {
+        let default = "diff".to_string();
+        let format = format.unwrap_or(default);
+
+        match format.as_str() {
+            "json" => serde_json::to_string_pretty(self).unwrap(),
+            "diff" => self
+                .files
+                .iter()
+                .map(|file| file.diff.as_str())
+                .collect::<Vec<&str>>()
+                .join(""),
+            _ => match suggest_subcommand(&format) {
+                Some(suggest) => {
+                    format!("Unknown format: '{}'. Did you mean '{}'?", format, suggest)
+                }
+                None => format!("Unknown format: '{}'", format),
+            },
+        }
+    }
+}
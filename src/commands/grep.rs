@@ -2,11 +2,11 @@ use std::path::PathBuf;
 use std::{io, path::Path};
 
 use regex::Regex;
-use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::fs;
 
 use serde::{Deserialize, Serialize};
 
+use crate::encoding::DecodedSource;
 use crate::utils::suggest_subcommand;
 
 use super::pattern_search::PatternTree;
@@ -96,17 +96,12 @@ impl GrepReport {
         pattern_tree: &mut PatternTree,
         patterns: &Vec<String>,
     ) -> io::Result<()> {
-        let file = File::open(path).await?;
-        let mut reader = BufReader::new(file);
+        let bytes = fs::read(path).await?;
+        let source = DecodedSource::from_file(&bytes, path)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, format!("{:#}", error)))?;
 
-        let mut line_bytes = Vec::new();
-        let mut i = 0;
-
-        while reader.read_until(b'\n', &mut line_bytes).await? > 0 {
-            let line = String::from_utf8_lossy(&line_bytes).to_string();
-            self.process_line(line, i, path, pattern_tree, patterns);
-            line_bytes.clear();
-            i += 1;
+        for (i, line) in source.text.split_inclusive('\n').enumerate() {
+            self.process_line(line.to_string(), i, path, pattern_tree, patterns);
         }
 
         Ok(())
@@ -1,3 +1,5 @@
 pub mod boyer_moore;
+pub mod diff;
 pub mod grep;
 pub mod pattern_search;
+pub mod strip;
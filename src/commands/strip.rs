@@ -0,0 +1,26 @@
+use anyhow::Result;
+
+use crate::analyzer::Analyzer;
+use crate::language::Language;
+use crate::tokens::Marker;
+
+/// Removes every TODO comment generated by balpan from the source code
+///
+/// Both `[TODO]` and `[DONE]` comments are removed, while hand-written comments which only
+/// look like them are kept, see `Analyzer::strip`. Every other line is kept as it is,
+/// including its line ending and a BOM, so stripping an analyzed file gives back the original file.
+/// Nothing is removed if the marker template of the language is invalid.
+pub fn strip_todo_comments(source_code: &str, language: &Language) -> Result<String> {
+    if Marker::from_language(language).is_err() {
+        return Ok(source_code.to_string());
+    }
+
+    let analyzer = Analyzer {
+        source_code: source_code.to_string(),
+        language: language.clone(),
+        indent_unit: None,
+        marker_placement: None,
+    };
+
+    analyzer.strip()
+}
@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+use crate::utils::expand_braces;
+
+const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// Properties of a file, resolved from `.editorconfig` files
+///
+/// See https://editorconfig.org for the specification. Only the properties
+/// related to the indentation and the charset are read.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EditorConfig {
+    pub indent_style: Option<String>,
+    pub indent_size: Option<String>,
+    pub tab_width: Option<String>,
+    pub charset: Option<String>,
+}
+
+impl EditorConfig {
+    /// Resolves the properties of `path` from the `.editorconfig` files of its directory
+    /// and of its ancestors, up to the one declaring `root = true`
+    pub fn resolve(path: &Path) -> Self {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut config_files: Vec<(PathBuf, String)> = vec![];
+
+        for dir in path.ancestors().skip(1) {
+            let Ok(content) = fs::read_to_string(dir.join(EDITORCONFIG_FILE_NAME)) else {
+                continue;
+            };
+
+            let is_root = is_root(&content);
+            config_files.push((dir.to_path_buf(), content));
+
+            if is_root {
+                break;
+            }
+        }
+
+        let mut config = EditorConfig::default();
+
+        // the closer `.editorconfig` is to the file, the higher precedence it takes
+        for (dir, content) in config_files.iter().rev() {
+            if let Ok(relative_path) = path.strip_prefix(dir) {
+                config.apply(content, relative_path);
+            }
+        }
+
+        config
+    }
+
+    /// Applies the sections of an `.editorconfig` file which match `relative_path`,
+    /// the path of the file relative to the directory of the `.editorconfig` file
+    pub fn apply(&mut self, content: &str, relative_path: &Path) {
+        let mut is_matched_section = false;
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                is_matched_section = section_matches(section, relative_path);
+                continue;
+            }
+
+            if !is_matched_section {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = Some(value.trim().to_lowercase());
+
+            match key.trim().to_lowercase().as_str() {
+                "indent_style" => self.indent_style = value,
+                "indent_size" => self.indent_size = value,
+                "tab_width" => self.tab_width = value,
+                "charset" => self.charset = value,
+                _ => {}
+            }
+        }
+    }
+
+    /// A unit of indentation, if the properties are enough to decide it
+    pub fn indent_unit(&self) -> Option<String> {
+        match self.indent_style.as_deref()? {
+            "tab" => Some("\t".to_string()),
+            "space" => {
+                let size = match self.indent_size.as_deref()? {
+                    "tab" => self.tab_width.as_deref()?,
+                    size => size,
+                };
+
+                size.parse::<usize>().ok().map(|size| " ".repeat(size))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks whether the preamble of an `.editorconfig` file declares `root = true`
+fn is_root(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .any(|(key, value)| {
+            key.trim().eq_ignore_ascii_case("root") && value.trim().eq_ignore_ascii_case("true")
+        })
+}
+
+fn section_matches(section: &str, relative_path: &Path) -> bool {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    // a pattern without `/` matches the file name in any directory
+    let section = match section.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if section.contains('/') => section.to_string(),
+        None => format!("**/{}", section),
+    };
+
+    expand_braces(&section)
+        .iter()
+        .filter_map(|pattern| Pattern::new(pattern).ok())
+        .any(|pattern| pattern.matches_path_with(relative_path, options))
+}
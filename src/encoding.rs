@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use crate::editorconfig::EditorConfig;
+
+/// Source code decoded from the bytes of a file, along with the encoding of the file
+pub struct DecodedSource {
+    pub text: String,
+    pub encoding: &'static Encoding,
+}
+
+impl DecodedSource {
+    /// Decodes a file, detecting its encoding from the file itself and its `.editorconfig`
+    pub fn from_file(bytes: &[u8], path: &Path) -> Result<Self> {
+        let charset = EditorConfig::resolve(path).charset;
+
+        DecodedSource::decode(bytes, charset.as_deref())
+    }
+
+    /// Decodes the bytes of a file
+    ///
+    /// The encoding is detected in the following order:
+    /// 1. the BOM of the file
+    /// 2. `charset`, e.g. `latin1` or `shift_jis`
+    /// 3. UTF-8 if the bytes are valid UTF-8
+    /// 4. the guess of a heuristic detector
+    ///
+    /// Fails if the bytes can't be encoded back exactly as they are, so that annotating
+    /// a file never changes the bytes which are not TODO comments.
+    pub fn decode(bytes: &[u8], charset: Option<&str>) -> Result<Self> {
+        let encoding = match Encoding::for_bom(bytes) {
+            Some((encoding, _)) => encoding,
+            None => match charset {
+                Some(charset) => encoding_for_charset(charset)?,
+                None => detect_encoding(bytes),
+            },
+        };
+
+        // the BOM is kept in the text, and written back along with the text
+        let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+        if had_errors {
+            bail!("Not a valid {} file", encoding.name());
+        }
+
+        let decoded = DecodedSource {
+            text: text.into_owned(),
+            encoding,
+        };
+
+        if decoded.encode(&decoded.text)? != bytes {
+            bail!("{} can't be written back as it is", encoding.name());
+        }
+
+        Ok(decoded)
+    }
+
+    /// Encodes text in the encoding of the decoded file
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        // encoding_rs writes UTF-8 for UTF-16, so UTF-16 is encoded by hand
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let to_bytes = if self.encoding == UTF_16LE {
+                u16::to_le_bytes
+            } else {
+                u16::to_be_bytes
+            };
+
+            return Ok(text.encode_utf16().flat_map(to_bytes).collect());
+        }
+
+        let (bytes, _, had_errors) = self.encoding.encode(text);
+        if had_errors {
+            bail!(
+                "Some characters can't be written in {}",
+                self.encoding.name()
+            );
+        }
+
+        Ok(bytes.into_owned())
+    }
+
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == UTF_8
+    }
+}
+
+/// Finds the encoding of a `charset` of `.editorconfig`, which also accepts
+/// any label of the WHATWG encoding standard, e.g. `shift_jis`
+fn encoding_for_charset(charset: &str) -> Result<&'static Encoding> {
+    let label = match charset {
+        "utf-8-bom" => "utf-8",
+        "latin1" => "iso-8859-1",
+        label => label,
+    };
+
+    Encoding::for_label(label.as_bytes()).ok_or_else(|| anyhow!("Unknown charset `{}`", charset))
+}
+
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
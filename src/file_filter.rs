@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use serde::Deserialize;
+
+use crate::utils::expand_braces;
+
+/// Name of the file which lists the files balpan skips, in the syntax of `.gitignore`
+pub const BALPANIGNORE_FILE_NAME: &str = ".balpanignore";
+
+/// Files which are not annotated unless `exclude` is set in `.balpan/config.toml`
+///
+/// They are test files, which are still searched by `grep`, see `FileFilter::without_default_exclude`.
+pub const DEFAULT_EXCLUDE: &[&str] = &[
+    "**/test/**",
+    "**/tests/**",
+    "**/__tests__/**",
+    "test_*.*",
+    "*_test.*",
+    "*.test.*",
+    "*_spec.*",
+    "*.spec.*",
+];
+
+/// The `[files]` table of `.balpan/config.toml`
+///
+/// ```toml
+/// [files]
+/// include = ["src/**"]
+/// exclude = ["**/tests/**", "*_test.*"]
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FilesConfiguration {
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WorkspaceConfiguration {
+    files: Option<FilesConfiguration>,
+}
+
+/// Decides which files of the workspace are annotated and searched
+///
+/// A file is kept if it matches one of the `include` patterns, or if there are none,
+/// and matches none of the `exclude` patterns. The patterns are globs matched against
+/// the path relative to the workspace root, and a pattern without `/` matches the
+/// file name in any directory, e.g. `*_test.*` matches `src/parser_test.rs`.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    default_exclude: Vec<Pattern>,
+}
+
+impl Default for FileFilter {
+    fn default() -> Self {
+        FileFilter::new(&FilesConfiguration::default())
+            .expect("the default patterns to exclude are valid")
+    }
+}
+
+impl FileFilter {
+    pub fn new(configuration: &FilesConfiguration) -> Result<Self> {
+        let include = configuration.include.as_deref().unwrap_or_default();
+        let (exclude, default_exclude) = match &configuration.exclude {
+            Some(exclude) => (exclude.clone(), vec![]),
+            None => (
+                vec![],
+                DEFAULT_EXCLUDE.iter().map(|p| p.to_string()).collect(),
+            ),
+        };
+
+        Ok(FileFilter {
+            include: compile_patterns(include)?,
+            exclude: compile_patterns(&exclude)?,
+            default_exclude: compile_patterns(&default_exclude)?,
+        })
+    }
+
+    /// Keeps the files of `DEFAULT_EXCLUDE`, e.g. to search test files as well
+    ///
+    /// The patterns of `exclude` in `.balpan/config.toml` still apply.
+    pub fn without_default_exclude(mut self) -> Self {
+        self.default_exclude.clear();
+        self
+    }
+
+    /// Reads the `[files]` table of a workspace config file, e.g. `.balpan/config.toml`
+    ///
+    /// A missing file, or a file without the table, gives the default filter.
+    pub fn from_config_file(path: &Path) -> Result<Self> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Ok(FileFilter::default());
+        };
+
+        FileFilter::from_toml(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let configuration: WorkspaceConfiguration = toml::from_str(content)?;
+
+        FileFilter::new(&configuration.files.unwrap_or_default())
+    }
+
+    /// Checks whether a file is kept, given its path relative to the workspace root
+    pub fn is_included(&self, relative_path: &Path) -> bool {
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        let matches = |pattern: &Pattern| pattern.matches_path_with(relative_path, options);
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+            && !self.default_exclude.iter().any(matches)
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>> {
+    let mut compiled = vec![];
+
+    for pattern in patterns {
+        // a pattern without `/` matches the file name in any directory
+        let anchored = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') => pattern.to_string(),
+            None => format!("**/{}", pattern),
+        };
+
+        for expanded in expand_braces(&anchored) {
+            compiled.push(
+                Pattern::new(&expanded)
+                    .with_context(|| format!("Invalid file pattern `{}`", pattern))?,
+            );
+        }
+    }
+
+    Ok(compiled)
+}
@@ -1,11 +1,30 @@
-#[derive(PartialEq)]
+use std::collections::HashMap;
+
+use once_cell::sync::{Lazy, OnceCell};
+use regex::Regex;
+use serde::Deserialize;
+use tree_sitter::Query;
+
+use crate::config::{default_lang_config, user_lang_config};
+use crate::symbols::symbols_query_source;
+
+#[derive(Clone, PartialEq)]
 pub enum Language {
     Rust,
     Python,
     Ruby,
+    C,
     Cpp,
     TypeScript,
+    Tsx,
     JavaScript,
+    Jsx,
+    Go,
+    Java,
+    Kotlin,
+    CSharp,
+    Php,
+    Elixir,
     Other(String),
 }
 
@@ -15,60 +34,166 @@ impl Language {
             Self::Rust => "rust",
             Self::Python => "python",
             Self::Ruby => "ruby",
+            Self::C => "c",
             Self::Cpp => "cpp",
             Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
             Self::JavaScript => "javascript",
+            Self::Jsx => "jsx",
+            Self::Go => "go",
+            Self::Java => "java",
+            Self::Kotlin => "kotlin",
+            Self::CSharp => "c-sharp",
+            Self::Php => "php",
+            Self::Elixir => "elixir",
             Self::Other(ref language) => language.as_str(),
         }
     }
 
-    #[inline]
+    /// Finds the language whose `file-types` in languages.toml contains the extension
     pub fn from_extension(extension: &str) -> Self {
+        LANGUAGE_DEFINITIONS
+            .iter()
+            .filter(|definition| definition.is_supported())
+            .find(|definition| {
+                definition
+                    .file_types
+                    .iter()
+                    .any(|file_type| file_type.as_str() == Some(extension))
+            })
+            .map(|definition| Self::from(definition.name.as_str()))
+            .unwrap_or_else(|| Self::Other(extension.to_string()))
+    }
+
+    /// Detects the language of a file from its extension and its source code
+    ///
+    /// `.h` files are shared by C and C++, so a header is treated as C++
+    /// only if it contains any construct which is not valid in C.
+    pub fn from_source_code(extension: &str, source_code: &str) -> Self {
         match extension {
-            "rs" => Self::Rust,
-            "py" => Self::Python,
-            "rb" => Self::Ruby,
-            "cpp" => Self::Cpp,
-            "h" => Self::Cpp,
-            "hpp" => Self::Cpp,
-            "ts" => Self::TypeScript,
-            "js" => Self::JavaScript,
-            other_extension => Self::Other(other_extension.to_string()),
+            "h" if is_cpp_header(source_code) => Self::Cpp,
+            other_extension => Self::from_extension(other_extension),
         }
     }
 
-    /// language specific tree-sitter node types
-    pub fn top_level_node_type(&self) -> &str {
-        match self {
-            Language::Rust => "source_file",
-            Language::Python => "module",
-            Language::Ruby | Language::JavaScript | Language::TypeScript => "program",
-            Language::Cpp => "translation_unit",
-            _ => "",
-        }
+    /// Name of the tree-sitter grammar which parses the language,
+    /// e.g. `javascript` for jsx, whose `grammar` is set in languages.toml
+    pub fn grammar_name(&self) -> &str {
+        self.definition()
+            .and_then(|definition| definition.grammar.as_deref())
+            .unwrap_or_else(|| self.as_str())
     }
 
-    pub fn decorator_node_type(&self) -> &str {
-        match self {
-            Language::Rust => "attribute_item",
-            Language::Python | Language::Ruby | Language::Cpp => "null",
-            Language::TypeScript | Language::JavaScript => "decorator",
-            _ => "",
-        }
+    /// The `symbols.scm` query of the language, compiled once for `grammar`
+    pub fn symbols_query(&self, grammar: tree_sitter::Language) -> Option<&'static Query> {
+        let definition = self.definition()?;
+
+        definition
+            .symbols_query
+            .get_or_init(|| {
+                let source = symbols_query_source(self.as_str())?;
+
+                Query::new(grammar, &source)
+                    .map_err(|error| {
+                        eprintln!("Invalid symbols.scm query of {}: {}", self.as_str(), error);
+                    })
+                    .ok()
+            })
+            .as_ref()
     }
 
-    pub fn comment_node_type(&self) -> &str {
-        match self {
-            Language::Rust => "line_comment",
-            Language::Python
-            | Language::Ruby
-            | Language::Cpp
-            | Language::TypeScript
-            | Language::JavaScript => "comment",
-            _ => "",
+    fn definition(&self) -> Option<&'static LanguageDefinition> {
+        LANGUAGE_DEFINITIONS
+            .iter()
+            .find(|definition| definition.name == self.as_str())
+    }
+
+    /// Whether balpan knows which symbols of the language get TODO comments
+    pub fn is_supported(&self) -> bool {
+        self.definition()
+            .is_some_and(|definition| definition.is_supported())
+    }
+
+    /// Comment token which starts a TODO comment, e.g. `///` for `/// [TODO] main`
+    pub fn todo_comment_token(&self) -> Option<&str> {
+        let definition = self
+            .definition()
+            .filter(|definition| definition.is_supported())?;
+
+        definition
+            .todo_comment_token
+            .as_deref()
+            .or(definition.comment_token.as_deref())
+    }
+
+    pub fn marker_placement(&self) -> MarkerPlacement {
+        self.marker_configuration().placement.unwrap_or_default()
+    }
+
+    /// What happens to the test code of the language, see `TestCode`
+    pub fn test_code(&self) -> TestCode {
+        self.marker_configuration().test_code.unwrap_or_default()
+    }
+
+    /// Patterns which recognize the test code of the language
+    pub fn test_patterns(&self) -> &'static TestPatterns {
+        static EMPTY: Lazy<TestPatterns> = Lazy::new(TestPatterns::default);
+
+        TEST_PATTERNS.get(self.as_str()).unwrap_or(&EMPTY)
+    }
+
+    /// Comment token of the language, e.g. `//` in rust
+    pub fn comment_token(&self) -> Option<&str> {
+        self.definition()
+            .filter(|definition| definition.is_supported())?
+            .comment_token
+            .as_deref()
+    }
+
+    /// Tokens which start a doc comment, e.g. `///` and `/**` in rust.
+    /// A TODO comment is placed above the doc comment of a symbol, not between them,
+    /// unless the TODO comment starts with one of these tokens.
+    pub fn doc_comment_tokens(&self) -> Vec<&str> {
+        self.node_types(|definition| &definition.doc_comment_tokens)
+    }
+
+    /// Whether a blank line separates a TODO comment from the doc comment below it,
+    /// e.g. in go, where every comment right above a declaration is its doc comment
+    pub fn separates_doc_comments(&self) -> bool {
+        self.definition()
+            .is_some_and(|definition| definition.separate_doc_comments)
+    }
+
+    /// Marker settings of the language, falling back to the `[marker]` table of languages.toml
+    pub fn marker_configuration(&self) -> MarkerConfiguration {
+        match self
+            .definition()
+            .and_then(|definition| definition.marker.as_ref())
+        {
+            Some(marker) => marker.or(&MARKER_CONFIGURATION),
+            None => MARKER_CONFIGURATION.clone(),
         }
     }
 
+    /// A unit of indentation, repeated for each level of nested TODO comments whose
+    /// indentation can't be taken from the line of the symbol
+    pub fn indent_unit(&self) -> &str {
+        self.definition()
+            .and_then(|definition| definition.indent.as_ref())
+            .map_or("    ", |indent| indent.unit.as_str())
+    }
+
+    /// language specific tree-sitter node types
+    pub fn top_level_node_type(&self) -> &str {
+        self.definition()
+            .map_or("", |definition| definition.top_level_node.as_str())
+    }
+
+    pub fn decorator_node_type(&self) -> &str {
+        self.definition()
+            .map_or("", |definition| definition.decorator_node.as_str())
+    }
+
     pub fn scannable_node_types(&self) -> Vec<&str> {
         let mut scannable = self.ignorable_node_types();
         let mut commentable = self.commentable_node_types();
@@ -77,74 +202,54 @@ impl Language {
     }
 
     pub fn ignorable_node_types(&self) -> Vec<&str> {
-        match self {
-            Language::Rust => vec![
-                "type_item",
-                "static_item",
-                "extern_crate_declaration",
-                "const_item",
-                "use_declaration",
-                "expression_statement",
-                "macro_invocation",
-                "foreign_mod_item", // extern "C"
-            ],
-            Language::TypeScript | Language::JavaScript => {
-                vec!["string_fragment", "import_specifier", "named_imports"]
-            }
-            _ => vec![],
-        }
+        self.node_types(|definition| &definition.ignorable_nodes)
     }
 
     pub fn commentable_node_types(&self) -> Vec<&str> {
-        match self {
-            Language::Rust => vec![
-                "attribute_item",
-                "mod_item",
-                "enum_item",
-                "impl_item",
-                "function_item",
-                "struct_item",
-                "trait_item",
-                "macro_definition",
-            ],
-            Language::Python => vec![
-                "class_definition",
-                "function_definition",
-                "decorated_definition",
-            ],
-            Language::Ruby => vec!["class", "method", "function", "module"],
-            Language::Cpp => vec![
-                "namespace_definition",
-                "function_definition",
-                "class_specifier",
-            ],
-            Language::TypeScript | Language::JavaScript => vec![
-                "enum_declaration",
-                "function_declaration",
-                "class_declaration",
-                "method_definition",
-                "interface_declaration",
-                "export_statement",
-                // "variable_declaration",
-                "expression_statement", // namespace
-            ],
-            _ => vec![],
-        }
+        self.node_types(|definition| &definition.commentable_nodes)
     }
 
     pub fn nested_traversable_symbols(&self) -> Vec<&str> {
-        match self {
-            Language::Rust => vec!["mod_item", "impl_item"],
-            Language::Python => vec!["class_definition"],
-            Language::Ruby => vec!["class", "module"],
-            Language::Cpp => vec!["namespace_definition", "class_specifier"],
-            Language::TypeScript | Language::JavaScript => vec![
-                "class_declaration",
-                "expression_statement",
-                "internal_module",
-            ],
-            _ => vec![],
-        }
+        self.definition()
+            .map(|definition| {
+                definition
+                    .nested_traversable_nodes
+                    .iter()
+                    .map(TraversableNode::kind)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Maximum depth of nested TODO comments, e.g. `0` for top-level symbols only
+    pub fn max_depth(&self) -> Option<usize> {
+        self.definition()
+            .and_then(|definition| definition.max_depth)
+    }
+
+    /// Whether the members of a node of `node_type` at `depth` get nested TODO comments
+    pub fn is_traversable(&self, node_type: &str, depth: usize) -> bool {
+        let is_within = |max_depth: Option<usize>| max_depth.is_none_or(|max| depth < max);
+
+        is_within(self.max_depth())
+            && self.definition().is_some_and(|definition| {
+                definition
+                    .nested_traversable_nodes
+                    .iter()
+                    .any(|node| node.kind() == node_type && is_within(node.max_depth()))
+            })
+    }
+
+    /// Node types which are not commented by themselves,
+    /// but whose child nodes are scanned as members of the enclosing scope
+    pub fn transparent_node_types(&self) -> Vec<&str> {
+        self.node_types(|definition| &definition.transparent_nodes)
+    }
+
+    fn node_types(&self, select: fn(&LanguageDefinition) -> &Vec<String>) -> Vec<&str> {
+        self.definition()
+            .map(|definition| select(definition).iter().map(String::as_str).collect())
+            .unwrap_or_default()
     }
 }
 
@@ -154,10 +259,418 @@ impl From<&str> for Language {
             "rust" => Self::Rust,
             "python" => Self::Python,
             "ruby" => Self::Ruby,
+            "c" => Self::C,
             "cpp" => Self::Cpp,
             "typescript" => Self::TypeScript,
+            "tsx" => Self::Tsx,
             "javascript" => Self::JavaScript,
+            "jsx" => Self::Jsx,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            "kotlin" => Self::Kotlin,
+            "c-sharp" => Self::CSharp,
+            "php" => Self::Php,
+            "elixir" => Self::Elixir,
             other_language => Self::Other(other_language.to_string()),
         }
     }
 }
+
+/// Settings of a language declared in languages.toml
+///
+/// Besides the keys shared with helix, balpan reads which tree-sitter nodes are
+/// commented from the following keys, so that a language can be added or tweaked
+/// in `.balpan/languages.toml`.
+///
+/// ```toml
+/// [[language]]
+/// name = "rust"
+/// file-types = ["rs"]
+/// comment-token = "//"
+/// indent = { tab-width = 4, unit = "    " }
+/// todo-comment-token = "///"
+/// doc-comment-tokens = ["///", "/**"]
+/// separate-doc-comments = false
+/// marker = { template = "{comment} REVIEW({state}) {path}" }
+/// top-level-node = "source_file"
+/// decorator-node = "attribute_item"
+/// ignorable-nodes = ["use_declaration"]
+/// commentable-nodes = ["mod_item", "function_item"]
+/// nested-traversable-nodes = ["mod_item", { kind = "trait_item", max-depth = 1 }]
+/// max-depth = 2
+/// transparent-nodes = []
+/// test-attributes = ['^#\[((\w+::)*test(\(.*\))?|cfg\(test\))\]$']
+/// test-names = []
+/// test-superclasses = []
+/// test-calls = []
+/// ```
+///
+/// The members of `nested-traversable-nodes` get nested TODO comments, see `TraversableNode`.
+/// `max-depth` limits how deep nested TODO comments go in the language, e.g. `0` annotates
+/// top-level symbols only. A node of `transparent-nodes` is not annotated, but its child nodes
+/// are scanned as if they were children of its parent, e.g. `["assignment_expression", "object"]`
+/// in javascript descends into the methods of `module.exports = { ... }`.
+///
+/// A TODO comment goes above the doc comment of a symbol, see `doc-comment-tokens`.
+/// With `separate-doc-comments = true`, a blank line is put between them, so that
+/// the TODO comment doesn't become a part of the doc comment, e.g. in go.
+///
+/// The `test-*` keys recognize test code, see `TestPatterns`.
+///
+/// If the language has a `symbols.scm` query, e.g. rust, python, go, java, c and javascript,
+/// the query finds and names the symbols, and `commentable-nodes` and `ignorable-nodes` still
+/// decide which of them are commented. A node type removed from `commentable-nodes` is not
+/// commented, but a node type added to it is commented only if the query finds it as well.
+/// The symbols of the other languages are named by the analyzer itself.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LanguageDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub file_types: Vec<toml::Value>, // `{ suffix = ".git/config" }` is also allowed
+    pub grammar: Option<String>,
+    pub comment_token: Option<String>,
+    pub todo_comment_token: Option<String>,
+    #[serde(default)]
+    pub doc_comment_tokens: Vec<String>,
+    #[serde(default)]
+    pub separate_doc_comments: bool,
+    pub marker: Option<MarkerConfiguration>,
+    pub indent: Option<IndentationConfiguration>,
+    #[serde(default)]
+    pub top_level_node: String,
+    #[serde(default)]
+    pub decorator_node: String,
+    #[serde(default)]
+    pub ignorable_nodes: Vec<String>,
+    #[serde(default)]
+    pub commentable_nodes: Vec<String>,
+    #[serde(default)]
+    pub nested_traversable_nodes: Vec<TraversableNode>,
+    pub max_depth: Option<usize>,
+    #[serde(default)]
+    pub transparent_nodes: Vec<String>,
+    #[serde(default)]
+    pub test_attributes: Vec<String>,
+    #[serde(default)]
+    pub test_names: Vec<String>,
+    #[serde(default)]
+    pub test_superclasses: Vec<String>,
+    #[serde(default)]
+    pub test_calls: Vec<String>,
+    #[serde(skip)]
+    symbols_query: OnceCell<Option<Query>>,
+}
+
+/// A node type whose members get nested TODO comments
+///
+/// A table descends only into the nodes whose depth is less than `max-depth`,
+/// e.g. `{ kind = "class_definition", max-depth = 1 }` descends into top-level classes,
+/// but not into the classes nested in them.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum TraversableNode {
+    Kind(String),
+    #[serde(rename_all = "kebab-case")]
+    Limited {
+        kind: String,
+        max_depth: usize,
+    },
+}
+
+impl TraversableNode {
+    pub fn kind(&self) -> &str {
+        match self {
+            Self::Kind(kind) | Self::Limited { kind, .. } => kind,
+        }
+    }
+
+    pub fn max_depth(&self) -> Option<usize> {
+        match self {
+            Self::Kind(_) => None,
+            Self::Limited { max_depth, .. } => Some(*max_depth),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndentationConfiguration {
+    pub unit: String,
+}
+
+/// How TODO comments are written
+///
+/// The `[marker]` table of languages.toml applies to every language, and the `marker`
+/// key of a language overrides it. `{comment}` is replaced with the `todo-comment-token`
+/// of the language, `{state}` with one of the states and `{path}` with the symbol path.
+///
+/// `placement` decides where a TODO comment goes, see `MarkerPlacement`,
+/// and `test-code` what happens to test code, see `TestCode`.
+///
+/// With `plain-comment = true`, `{comment}` is replaced with the `comment-token` instead,
+/// e.g. `//` rather than `///` in rust, so that TODO comments don't end up in the docs.
+/// It is the default of rust, c and c++. A language without `comment-token` falls back
+/// to its `todo-comment-token`.
+///
+/// ```toml
+/// [marker]
+/// template = "{comment} [{state}] {path}"
+/// todo-state = "TODO"
+/// done-state = "DONE"
+/// plain-comment = false
+/// placement = "above-decorators"
+/// test-code = "annotate"
+/// test-state = "TEST"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MarkerConfiguration {
+    pub template: Option<String>,
+    pub todo_state: Option<String>,
+    pub done_state: Option<String>,
+    pub plain_comment: Option<bool>,
+    pub placement: Option<MarkerPlacement>,
+    pub test_code: Option<TestCode>,
+    pub test_state: Option<String>,
+}
+
+/// Where a TODO comment is placed, relative to its symbol
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkerPlacement {
+    /// Above the decorators of the symbol, or above its declaration if it has none
+    #[default]
+    AboveDecorators,
+    /// Right above the declaration, below its decorators
+    AboveDeclaration,
+    /// Above the first statement of the body, after the docstring.
+    /// Falls back to `AboveDeclaration` if the body has no line of its own.
+    StartOfBody,
+}
+
+/// What happens to test code, e.g. `#[test] fn` in rust or `def test_foo()` in python
+///
+/// Every symbol inside test code, e.g. a function of `#[cfg(test)] mod tests`,
+/// is treated as test code as well.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TestCode {
+    /// Annotated like any other code
+    #[default]
+    Annotate,
+    /// Not annotated. The TODO comments already written above it are removed.
+    Skip,
+    /// Annotated with the test state of the marker instead of the TODO state
+    Mark,
+}
+
+/// Recognizes test code from the `test-*` keys of a language in languages.toml
+///
+/// A symbol is test code if any of the following matches:
+/// - `test-attributes`: a regex matched against each decorator of the symbol,
+///   e.g. `#[test]` in rust
+/// - `test-names`: a regex matched against the name of the symbol, e.g. `^test_` in python
+/// - `test-superclasses`: a regex matched against each superclass of the symbol,
+///   e.g. `unittest.TestCase` in python
+/// - `test-calls`: the name of a function whose calls define tests, e.g. `describe`
+///   in javascript. Such calls are annotated even if their node is not commentable.
+#[derive(Debug, Default)]
+pub struct TestPatterns {
+    pub attributes: Vec<Regex>,
+    pub names: Vec<Regex>,
+    pub superclasses: Vec<Regex>,
+    pub calls: Vec<String>,
+}
+
+impl TestPatterns {
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+            && self.names.is_empty()
+            && self.superclasses.is_empty()
+            && self.calls.is_empty()
+    }
+
+    fn from_definition(definition: &LanguageDefinition) -> Self {
+        let compile = |patterns: &Vec<String>| -> Vec<Regex> {
+            patterns
+                .iter()
+                .filter_map(|pattern| match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(error) => {
+                        eprintln!(
+                            "Ignoring the invalid test pattern of {}: {}",
+                            definition.name, error
+                        );
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        TestPatterns {
+            attributes: compile(&definition.test_attributes),
+            names: compile(&definition.test_names),
+            superclasses: compile(&definition.test_superclasses),
+            calls: definition.test_calls.clone(),
+        }
+    }
+}
+
+impl MarkerConfiguration {
+    /// Fills the keys which are not set with the ones of `fallback`
+    pub fn or(&self, fallback: &MarkerConfiguration) -> MarkerConfiguration {
+        MarkerConfiguration {
+            template: self.template.clone().or_else(|| fallback.template.clone()),
+            todo_state: self
+                .todo_state
+                .clone()
+                .or_else(|| fallback.todo_state.clone()),
+            done_state: self
+                .done_state
+                .clone()
+                .or_else(|| fallback.done_state.clone()),
+            plain_comment: self.plain_comment.or(fallback.plain_comment),
+            placement: self.placement.or(fallback.placement),
+            test_code: self.test_code.or(fallback.test_code),
+            test_state: self
+                .test_state
+                .clone()
+                .or_else(|| fallback.test_state.clone()),
+        }
+    }
+
+    /// Comment token which replaces `{comment}` of the template
+    pub fn comment_token<'a>(&self, language: &'a Language) -> Option<&'a str> {
+        match self.plain_comment {
+            Some(true) => language
+                .comment_token()
+                .or_else(|| language.todo_comment_token()),
+            _ => language.todo_comment_token(),
+        }
+    }
+}
+
+impl LanguageDefinition {
+    fn is_supported(&self) -> bool {
+        !self.commentable_nodes.is_empty()
+    }
+}
+
+static LANGUAGE_CONFIG: Lazy<toml::Value> = Lazy::new(|| {
+    user_lang_config().unwrap_or_else(|error| {
+        eprintln!(
+            "Could not parse languages.toml, using the default one: {}",
+            error
+        );
+        default_lang_config()
+    })
+});
+
+static LANGUAGE_DEFINITIONS: Lazy<Vec<LanguageDefinition>> = Lazy::new(|| {
+    LANGUAGE_CONFIG
+        .get("language")
+        .and_then(|languages| languages.as_array())
+        .map(|languages| {
+            languages
+                .iter()
+                .filter_map(|language| match language.clone().try_into() {
+                    Ok(definition) => Some(definition),
+                    Err(error) => {
+                        let name = language.get("name").and_then(|name| name.as_str());
+                        eprintln!(
+                            "Ignoring the invalid definition of language {}: {}",
+                            name.unwrap_or("<unnamed>"),
+                            error
+                        );
+                        None
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+});
+
+static TEST_PATTERNS: Lazy<HashMap<String, TestPatterns>> = Lazy::new(|| {
+    LANGUAGE_DEFINITIONS
+        .iter()
+        .map(|definition| {
+            (
+                definition.name.clone(),
+                TestPatterns::from_definition(definition),
+            )
+        })
+        .collect()
+});
+
+static MARKER_CONFIGURATION: Lazy<MarkerConfiguration> = Lazy::new(|| {
+    let Some(marker) = LANGUAGE_CONFIG.get("marker") else {
+        return MarkerConfiguration::default();
+    };
+
+    marker.clone().try_into().unwrap_or_else(|error| {
+        eprintln!(
+            "Ignoring the invalid [marker] table of languages.toml: {}",
+            error
+        );
+        MarkerConfiguration::default()
+    })
+});
+
+/// Marker settings of the `[marker]` table of languages.toml, shared by every language
+pub fn marker_configuration() -> &'static MarkerConfiguration {
+    &MARKER_CONFIGURATION
+}
+
+/// Checks whether a header has any construct of C++ which is not valid in C,
+/// e.g. `template <typename T>` or `namespace foo {`, outside its comments
+fn is_cpp_header(source_code: &str) -> bool {
+    static CPP_ONLY_CONSTRUCTS: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r#"\btemplate\s*<|\bclass\s+\w+\s*[:{]|\bnamespace\s+\w+\s*\{|\bextern\s+"C\+\+""#,
+        )
+        .expect("the pattern of C++ constructs is valid")
+    });
+
+    CPP_ONLY_CONSTRUCTS.is_match(&strip_c_comments(source_code))
+}
+
+/// Replaces the `//` and `/* */` comments of C source code with a space,
+/// leaving string and character literals as they are
+fn strip_c_comments(source_code: &str) -> String {
+    let mut result = String::with_capacity(source_code.len());
+    let mut chars = source_code.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                result.push(' ');
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                result.push(' ');
+            }
+            ('"' | '\'', _) => {
+                result.push(c);
+                while let Some(next) = chars.next() {
+                    result.push(next);
+                    if next == '\\' {
+                        result.extend(chars.next());
+                    } else if next == c || next == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
@@ -3,9 +3,13 @@
 pub mod analyzer;
 pub mod commands;
 pub mod config;
+pub mod editorconfig;
+pub mod encoding;
+pub mod file_filter;
 pub mod grammar;
 pub mod language;
 pub mod scanner;
+pub mod symbols;
 pub mod tokens;
 pub mod tree_sitter_extended;
 pub mod utils;
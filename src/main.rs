@@ -5,9 +5,12 @@ use balpan::commands::pattern_search::PatternTree;
 use clap::{Parser, Subcommand};
 use glob::glob;
 
+use balpan::commands::diff::DiffReport;
 use balpan::commands::grep::GrepReport;
-use balpan::scanner::Scanner;
-use balpan::utils::{get_current_repository, list_available_files, suggest_subcommand};
+use balpan::language::marker_configuration;
+use balpan::scanner::{FileError, Scanner};
+use balpan::tokens::{file_search_patterns, search_patterns};
+use balpan::utils::{get_current_repository, list_searchable_files, suggest_subcommand};
 use git2::Repository;
 use tokio::runtime::{Builder, Runtime};
 
@@ -21,9 +24,29 @@ struct BalpanApp {
 #[derive(Debug, Subcommand)]
 enum BalpanCommand {
     #[clap(about = "Setup environment for Balpan and fetch all available treesitter parsers")]
-    Init,
-    #[clap(about = "Reset environment for Balpan and removes all TODO comments")]
+    Init {
+        #[clap(
+            long,
+            help = "Display the TODO comments that would be added, without modifying any file"
+        )]
+        dry_run: bool,
+        #[clap(
+            long,
+            help = "Apply formatting to the dry run output. Available options: json, diff (default)"
+        )]
+        format: Option<String>,
+    },
+    #[clap(about = "Reset environment for Balpan by deleting the onboarding branch")]
     Reset,
+    #[clap(about = "Remove all TODO comments generated by Balpan")]
+    Strip {
+        #[clap(
+            short,
+            long,
+            help = "Specific file to strip. All files of the repository are stripped if omitted"
+        )]
+        pattern: Option<String>,
+    },
     #[clap(
         about = "Searches a particular pattern of characters, and displays all lines that contain that pattern"
     )]
@@ -76,6 +99,16 @@ enum BalpanCommand {
     Analyze {
         #[clap(short, long, help = "Specific file to scan")]
         pattern: Option<String>,
+        #[clap(
+            long,
+            help = "Display the TODO comments that would be added, without modifying any file"
+        )]
+        dry_run: bool,
+        #[clap(
+            long,
+            help = "Apply formatting to the dry run output. Available options: json, diff (default)"
+        )]
+        format: Option<String>,
     },
 }
 
@@ -96,12 +129,21 @@ fn main() {
     }
 
     match app.command {
-        BalpanCommand::Init => {
+        BalpanCommand::Init { dry_run, format } => {
             let runtime = create_runtime();
 
-            runtime.block_on(async { handle_init().await })
+            if dry_run {
+                runtime.block_on(async { handle_init_dry_run(format).await })
+            } else {
+                runtime.block_on(async { handle_init().await })
+            }
         }
         BalpanCommand::Reset => handle_reset(),
+        BalpanCommand::Strip { pattern } => {
+            let runtime = create_runtime();
+
+            runtime.block_on(async { handle_strip(pattern).await })
+        }
         BalpanCommand::Grep {
             file,
             pattern,
@@ -141,7 +183,11 @@ fn main() {
                 println!("time: {:?}", time.elapsed());
             }
         }
-        BalpanCommand::Analyze { pattern } => {
+        BalpanCommand::Analyze {
+            pattern,
+            dry_run,
+            format,
+        } => {
             match pattern {
                 Some(ref p) => {
                     if !p.starts_with('"') || !p.ends_with('"') {
@@ -154,7 +200,11 @@ fn main() {
             let runtime = create_runtime();
 
             runtime.block_on(async {
-                handle_analyze(pattern).await;
+                if dry_run {
+                    handle_analyze_dry_run(pattern, format).await;
+                } else {
+                    handle_analyze(pattern).await;
+                }
             });
         }
     }
@@ -244,10 +294,77 @@ async fn handle_init() {
     git(vec!["switch".to_owned(), main_branch]);
     git(vec!["switch".to_owned(), "onboarding".to_owned()]);
 
-    Scanner::scan(&repo).await;
+    match Scanner::scan(&repo).await {
+        Ok(errors) => report_file_errors(&errors),
+        Err(error) => {
+            eprintln!("Failed to setup treesitter parsers: {:#}", error);
+            return;
+        }
+    }
+
     println!("init!");
 }
 
+/// Prints the files which couldn't be processed, so that the rest of the repository is still processed
+fn report_file_errors(errors: &[FileError]) {
+    if errors.is_empty() {
+        return;
+    }
+
+    eprintln!("Failed to process {} file(s):", errors.len());
+    for error in errors {
+        eprintln!("  {}", error);
+    }
+}
+
+async fn handle_strip(pattern: Option<String>) {
+    match pattern {
+        Some(file_pattern_str) => {
+            let filter = glob(&file_pattern_str).expect("Failed to read file pattern");
+            let mut errors = vec![];
+
+            for entry in filter {
+                match entry {
+                    Ok(path) => {
+                        if let Err(error) = Scanner::strip_specific_file(path.clone()).await {
+                            errors.push(FileError { path, error });
+                        }
+                    }
+                    Err(e) => println!("Error while reading file pattern: {}", e),
+                }
+            }
+
+            report_file_errors(&errors);
+        }
+        None => {
+            let repo = get_current_repository().unwrap();
+
+            match Scanner::strip(&repo).await {
+                Ok(errors) => report_file_errors(&errors),
+                Err(error) => eprintln!("Failed to setup treesitter parsers: {:#}", error),
+            }
+        }
+    }
+}
+
+async fn handle_init_dry_run(format: Option<String>) {
+    let repo = get_current_repository().unwrap();
+    let mut report = match repo.workdir() {
+        Some(workdir) => DiffReport::with_root(workdir),
+        None => DiffReport::new(),
+    };
+
+    match Scanner::scan_dry_run(&repo, &mut report).await {
+        Ok(errors) => report_file_errors(&errors),
+        Err(error) => {
+            eprintln!("Failed to setup treesitter parsers: {:#}", error);
+            return;
+        }
+    }
+
+    println!("{}", report.report_formatting(format));
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn handle_grep(
     file: Option<String>,
@@ -262,9 +379,9 @@ async fn handle_grep(
     extends_regex: bool,
 ) {
     let mut pattern_tree = PatternTree::new();
-    let default_patterns = vec!["[TODO]".to_string(), "[DONE]".to_string()];
 
-    let patterns_to_search: Vec<String>;
+    // without patterns, each file is searched for the TODO comments of its own language
+    let patterns_to_search: Option<Vec<String>>;
 
     if extends_regex {
         pattern_tree.ignore_case = true;
@@ -274,26 +391,33 @@ async fn handle_grep(
     match ignore_case {
         Some(ignore_patterns) => {
             pattern_tree.ignore_case = true;
-            patterns_to_search = ignore_patterns;
+            patterns_to_search = Some(ignore_patterns);
         }
         None => {
-            patterns_to_search = pattern.unwrap_or(default_patterns);
+            patterns_to_search = pattern;
         }
     }
 
-    match file {
+    let errors = match file {
         Some(file_path) => {
-            scan_specific_file(file_path, report, &mut pattern_tree, &patterns_to_search).await
+            scan_specific_file(
+                file_path,
+                report,
+                &mut pattern_tree,
+                patterns_to_search.as_ref(),
+            )
+            .await
         }
-        None => scan_project_directory(report, pattern_tree, patterns_to_search.clone()).await,
-    }
+        None => scan_project_directory(report, pattern_tree, patterns_to_search.as_ref()).await,
+    };
+    report_file_errors(&errors);
 
     let formatting = report.report_formatting(
         format,
         hide_path,
         list_of_files,
         count,
-        patterns_to_search,
+        patterns_to_search.unwrap_or_else(|| search_patterns(marker_configuration())),
         colorize,
     );
     println!("{}", formatting);
@@ -306,49 +430,109 @@ async fn handle_analyze(pattern: Option<String>) {
 
     let file_pattern_str = pattern.unwrap();
     let filter = glob(&file_pattern_str).expect("Failed to read file pattern");
+    let mut errors = vec![];
 
     for entry in filter {
         match entry {
-            Ok(path) => Scanner::scan_specific_file(path).await,
+            Ok(path) => {
+                if let Err(error) = Scanner::scan_specific_file(path.clone()).await {
+                    errors.push(FileError { path, error });
+                }
+            }
             Err(e) => println!("Error while reading file pattern: {}", e),
         }
     }
+
+    report_file_errors(&errors);
+}
+
+async fn handle_analyze_dry_run(pattern: Option<String>, format: Option<String>) {
+    if pattern.is_none() {
+        panic!("No file specified. Please specify a file path to analyze")
+    }
+
+    let file_pattern_str = pattern.unwrap();
+    let filter = glob(&file_pattern_str).expect("Failed to read file pattern");
+    let workdir = get_current_repository()
+        .and_then(|repo| repo.workdir().map(|workdir| workdir.to_path_buf()));
+    let mut report = match workdir {
+        Some(workdir) => DiffReport::with_root(&workdir),
+        None => DiffReport::new(),
+    };
+    let mut errors = vec![];
+
+    for entry in filter {
+        match entry {
+            Ok(path) => {
+                let result = Scanner::scan_specific_file_dry_run(path.clone(), &mut report).await;
+                if let Err(error) = result {
+                    errors.push(FileError { path, error });
+                }
+            }
+            Err(e) => println!("Error while reading file pattern: {}", e),
+        }
+    }
+
+    report_file_errors(&errors);
+    println!("{}", report.report_formatting(format));
 }
 
 async fn scan_project_directory(
     report: &mut GrepReport,
     mut pattern_tree: PatternTree,
-    patterns_to_search: Vec<String>,
-) {
+    patterns_to_search: Option<&Vec<String>>,
+) -> Vec<FileError> {
     let repo = get_current_repository().expect("No repository found");
     let repo_path = repo.workdir().expect("No workdir found").to_str().unwrap();
 
-    let available_files: Vec<String> = list_available_files(repo_path).await;
+    let available_files: Vec<String> = list_searchable_files(repo_path).await;
+    let mut errors = vec![];
 
     for file in available_files {
         let path = Path::new(&file);
-        update_report(report, path, &mut pattern_tree, &patterns_to_search).await;
+        if let Err(error) = update_report(report, path, &mut pattern_tree, patterns_to_search).await
+        {
+            errors.push(error);
+        }
     }
+
+    errors
 }
 
 async fn scan_specific_file(
     file_path: String,
     report: &mut GrepReport,
     pattern_tree: &mut PatternTree,
-    patterns_to_search: &Vec<String>,
-) {
+    patterns_to_search: Option<&Vec<String>>,
+) -> Vec<FileError> {
     let path = Path::new(&file_path);
-    update_report(report, path, pattern_tree, patterns_to_search).await;
+
+    match update_report(report, path, pattern_tree, patterns_to_search).await {
+        Ok(()) => vec![],
+        Err(error) => vec![error],
+    }
 }
 
 async fn update_report(
     report: &mut GrepReport,
     path: &Path,
     pattern_tree: &mut PatternTree,
-    patterns_to_search: &Vec<String>,
-) {
+    patterns_to_search: Option<&Vec<String>>,
+) -> Result<(), FileError> {
+    let default_patterns;
+    let patterns_to_search = match patterns_to_search {
+        Some(patterns) => patterns,
+        None => {
+            default_patterns = file_search_patterns(path);
+            &default_patterns
+        }
+    };
+
     report
         .grep_file(path, pattern_tree, patterns_to_search)
         .await
-        .unwrap();
+        .map_err(|error| FileError {
+            path: path.to_path_buf(),
+            error: error.into(),
+        })
 }
@@ -1,100 +1,322 @@
+use std::fmt;
 use std::fs::File;
-use std::io::{Read, Seek, Write};
+use std::io::{self, IsTerminal, Read, Seek, Write};
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant};
 
+use anyhow::{anyhow, Result};
 use git2::Repository;
 
 use crate::analyzer::Analyzer;
+use crate::commands::diff::DiffReport;
+use crate::commands::strip::strip_todo_comments;
+use crate::editorconfig::EditorConfig;
+use crate::encoding::DecodedSource;
 use crate::grammar::{build_grammars, fetch_grammars};
 use crate::language::Language;
 use crate::utils::list_available_files;
 
 pub struct Scanner;
 
+/// A file which couldn't be processed, along with the reason
+#[derive(Debug)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:#}", self.path.display(), self.error)
+    }
+}
+
 impl Scanner {
-    pub async fn scan(repo: &Repository) {
-        fetch_grammars().unwrap();
-        build_grammars(None).unwrap();
+    /// Add TODO comments to every file of the repository
+    ///
+    /// A file which fails to be processed doesn't stop the scan. Instead, the failure is
+    /// returned along with the failures of the other files.
+    pub async fn scan(repo: &Repository) -> Result<Vec<FileError>> {
+        fetch_grammars()?;
+        build_grammars(None)?;
+
+        let files = Scanner::collect_files(repo).await;
+        let results = Scanner::process_files(files, Scanner::scan_file);
+
+        Ok(errors_of(results))
+    }
+
+    /// Collect the changes `scan` would make to the repository, without writing them
+    pub async fn scan_dry_run(
+        repo: &Repository,
+        report: &mut DiffReport,
+    ) -> Result<Vec<FileError>> {
+        fetch_grammars()?;
+        build_grammars(None)?;
+
+        let files = Scanner::collect_files(repo).await;
+        let mut errors = vec![];
+
+        for (path, result) in Scanner::process_files(files, Scanner::diff_file) {
+            match result {
+                Ok((original, annotated)) => report.add_file(&path, &original, &annotated),
+                Err(error) => errors.push(FileError { path, error }),
+            }
+        }
+
+        Ok(errors)
+    }
+
+    /// Scan a specific file and add TODO comments
+    pub async fn scan_specific_file(path: PathBuf) -> Result<()> {
+        fetch_grammars()?;
+        build_grammars(None)?;
+
+        Scanner::scan_file(&path)
+    }
+
+    /// Collect the changes `scan_specific_file` would make to a file, without writing them
+    pub async fn scan_specific_file_dry_run(path: PathBuf, report: &mut DiffReport) -> Result<()> {
+        fetch_grammars()?;
+        build_grammars(None)?;
+
+        Scanner::scan_file_dry_run(&path, report)
+    }
+
+    /// Remove all TODO comments from the repository
+    pub async fn strip(repo: &Repository) -> Result<Vec<FileError>> {
+        fetch_grammars()?;
+        build_grammars(None)?;
+
+        let files = Scanner::collect_files(repo).await;
+        let results = Scanner::process_files(files, Scanner::strip_file);
+
+        Ok(errors_of(results))
+    }
+
+    /// Remove all TODO comments from a specific file
+    pub async fn strip_specific_file(path: PathBuf) -> Result<()> {
+        fetch_grammars()?;
+        build_grammars(None)?;
+
+        Scanner::strip_file(&path)
+    }
+
+    /// Processes the files on as many threads as the cores, and returns the results
+    /// in the order of `files`, regardless of the order the files are processed in
+    ///
+    /// A panic while processing a file fails only that file.
+    fn process_files<T, F>(files: Vec<PathBuf>, process: F) -> Vec<(PathBuf, Result<T>)>
+    where
+        T: Send,
+        F: Fn(&Path) -> Result<T> + Sync,
+    {
+        let thread_count = thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(files.len());
+        let next_index = AtomicUsize::new(0);
+        let mut results: Vec<Option<Result<T>>> = files.iter().map(|_| None).collect();
+        let mut progress = Progress::new(files.len());
+
+        thread::scope(|scope| {
+            let (tx, rx) = channel();
+
+            for _ in 0..thread_count {
+                let tx = tx.clone();
+                let (files, next_index, process) = (&files, &next_index, &process);
+
+                scope.spawn(move || loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = files.get(index) else {
+                        break;
+                    };
+
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| process(path)))
+                        .unwrap_or_else(|_| Err(anyhow!("Panicked while processing the file")));
+
+                    if tx.send((index, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+
+            drop(tx); // the receiver stops once every thread is done
+
+            for (index, result) in rx {
+                progress.update(result.is_err());
+                results[index] = Some(result);
+            }
+        });
+
+        progress.finish();
+
+        files
+            .into_iter()
+            .zip(results)
+            .filter_map(|(path, result)| result.map(|result| (path, result)))
+            .collect()
+    }
+
+    fn scan_file(path: &Path) -> Result<()> {
+        let mut file = File::options().read(true).write(true).open(path)?;
+        let (source, annotated) = Scanner::annotate(&mut file, path)?;
+
+        if annotated != source.text {
+            Scanner::overwrite(&mut file, &source.encode(&annotated)?)?;
+        }
+
+        Ok(())
+    }
+
+    fn scan_file_dry_run(path: &Path, report: &mut DiffReport) -> Result<()> {
+        let (original, annotated) = Scanner::diff_file(path)?;
+        report.add_file(path, &original, &annotated);
+
+        Ok(())
+    }
+
+    /// Returns the source code of the file before and after adding TODO comments
+    fn diff_file(path: &Path) -> Result<(String, String)> {
+        let mut file = File::open(path)?;
+        let (source, annotated) = Scanner::annotate(&mut file, path)?;
+
+        Ok((source.text, annotated))
+    }
+
+    fn strip_file(path: &Path) -> Result<()> {
+        let mut file = File::options().read(true).write(true).open(path)?;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+        let source = DecodedSource::from_file(&bytes, path)?;
+
+        let language = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => Language::from_extension(ext),
+            None => Language::Other(String::new()),
+        };
+
+        let stripped = strip_todo_comments(&source.text, &language)?;
+        if stripped != source.text {
+            Scanner::overwrite(&mut file, &source.encode(&stripped)?)?;
+        }
+
+        Ok(())
+    }
+
+    async fn collect_files(repo: &Repository) -> Vec<PathBuf> {
+        let mut result = vec![];
 
         if let Some(workdir) = repo.workdir() {
             let repo_root = workdir.to_string_lossy();
             let filenames = list_available_files(&repo_root);
             for filename in filenames.await {
-                if filename.contains("test") {
-                    continue;
-                }
                 let path = Path::new(&filename);
-                let language = match path.extension() {
-                    Some(os_str) => Language::from_extension(os_str.to_str().unwrap()),
-                    _ => Language::Other("".to_string()),
+                let language = match path.extension().and_then(|ext| ext.to_str()) {
+                    Some(ext) => Language::from_extension(ext),
+                    None => Language::Other("".to_string()),
                 };
 
-                if let Language::Other(_) = language {
+                if !language.is_supported() {
                     continue;
                 }
 
-                if let Ok(mut file) = File::options().read(true).write(true).open(path) {
-                    let mut source_code = String::new();
-                    file.read_to_string(&mut source_code).unwrap();
-                    let with_empty_line = source_code.ends_with('\n');
-                    let analyzer = Analyzer {
-                        source_code,
-                        language,
-                    };
+                result.push(path.to_path_buf());
+            }
+        }
 
-                    let writer_queue = &analyzer.analyze();
-                    let mut lines = vec![];
+        result
+    }
 
-                    for line in writer_queue {
-                        lines.push(String::from(line));
-                    }
+    /// Returns the decoded source code of the file and the source code with TODO comments
+    ///
+    /// The source code is decoded in the encoding of the file, which is written back along
+    /// with the TODO comments. A file which can't be decoded exactly as it is fails instead.
+    fn annotate(file: &mut File, path: &Path) -> Result<(DecodedSource, String)> {
+        let editorconfig = EditorConfig::resolve(path);
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+        let source = DecodedSource::decode(&bytes, editorconfig.charset.as_deref())?;
 
-                    if with_empty_line {
-                        lines.push(String::new());
-                    }
+        let language = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => Language::from_source_code(ext, &source.text),
+            None => Language::Other(String::new()),
+        };
 
-                    file.set_len(0).unwrap();
-                    file.rewind().unwrap();
-                    file.write_all(lines.join("\n").as_bytes()).unwrap();
-                }
-            }
+        let analyzer = Analyzer {
+            source_code: source.text.clone(),
+            language,
+            indent_unit: editorconfig.indent_unit(),
+            marker_placement: None,
+        };
+
+        let annotated = analyzer.annotate()?;
+
+        Ok((source, annotated))
+    }
+
+    fn overwrite(file: &mut File, bytes: &[u8]) -> Result<()> {
+        file.set_len(0)?;
+        file.rewind()?;
+        file.write_all(bytes)?;
+
+        Ok(())
+    }
+}
+
+fn errors_of(results: Vec<(PathBuf, Result<()>)>) -> Vec<FileError> {
+    results
+        .into_iter()
+        .filter_map(|(path, result)| result.err().map(|error| FileError { path, error }))
+        .collect()
+}
+
+/// Shows how many files are processed on stderr, if it is a terminal
+struct Progress {
+    total: usize,
+    done: usize,
+    failed: usize,
+    last_printed: Option<Instant>,
+    is_visible: bool,
+}
+
+impl Progress {
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    fn new(total: usize) -> Self {
+        Progress {
+            total,
+            done: 0,
+            failed: 0,
+            last_printed: None,
+            is_visible: io::stderr().is_terminal() && total > 1,
         }
     }
 
-    /// Scan a specific file and add TODO comments
-    pub async fn scan_specific_file(path: PathBuf) {
-        fetch_grammars().unwrap();
-        build_grammars(None).unwrap();
-
-        if let Ok(mut file) = File::options().read(true).write(true).open(path.clone()) {
-            let mut source_code = String::new();
-            file.read_to_string(&mut source_code).unwrap();
-            let with_empty_line = source_code.ends_with('\n');
-
-            let language = match path.extension() {
-                Some(p) => Language::from_extension(p.to_str().unwrap()),
-                _ => Language::Other(String::new()),
-            };
-
-            let analyzer = Analyzer {
-                source_code,
-                language,
-            };
-
-            let writer_queue = &analyzer.analyze();
-            let mut lines: Vec<String> = vec![];
-
-            for line in writer_queue {
-                lines.push(String::from(line));
-            }
+    fn update(&mut self, is_failed: bool) {
+        self.done += 1;
+        self.failed += usize::from(is_failed);
 
-            if with_empty_line {
-                lines.push(String::new());
-            }
+        let is_due = match self.last_printed {
+            Some(last_printed) => last_printed.elapsed() >= Progress::INTERVAL,
+            None => true,
+        };
+
+        if self.is_visible && (is_due || self.done == self.total) {
+            eprint!(
+                "\r{}/{} files processed, {} failed",
+                self.done, self.total, self.failed
+            );
+            self.last_printed = Some(Instant::now());
+        }
+    }
 
-            file.set_len(0).unwrap();
-            file.rewind().unwrap();
-            file.write_all(lines.join("\n").as_bytes()).unwrap();
+    fn finish(&self) {
+        if self.is_visible {
+            eprintln!();
         }
     }
 }
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use tree_sitter::{Node, Query, QueryCursor};
+
+use crate::grammar::load_runtime_file;
+
+const SYMBOLS_QUERY_FILE: &str = "symbols.scm";
+
+/// Names of the symbols found by a `symbols.scm` query, keyed by the id of their node
+pub type SymbolDefinitions = HashMap<usize, Option<String>>;
+
+/// Source of the `symbols.scm` query of a language
+///
+/// A query file placed in `runtime/queries/<language>/` takes precedence over the built-in one.
+pub fn symbols_query_source(language_name: &str) -> Option<String> {
+    load_runtime_file(language_name, SYMBOLS_QUERY_FILE)
+        .ok()
+        .or_else(|| builtin_symbols_query(language_name).map(String::from))
+}
+
+fn builtin_symbols_query(language_name: &str) -> Option<&'static str> {
+    match language_name {
+        "rust" => Some(include_str!("../queries/rust/symbols.scm")),
+        "python" => Some(include_str!("../queries/python/symbols.scm")),
+        "go" => Some(include_str!("../queries/go/symbols.scm")),
+        "java" => Some(include_str!("../queries/java/symbols.scm")),
+        "c" => Some(include_str!("../queries/c/symbols.scm")),
+        "javascript" | "jsx" => Some(include_str!("../queries/javascript/symbols.scm")),
+        _ => None,
+    }
+}
+
+/// Finds the symbols which get TODO comments
+///
+/// Every node captured as `@definition.<kind>` (e.g. `@definition.function`) is a symbol,
+/// and the text of the `@name` capture in the same match becomes its name.
+/// A symbol without `@name` capture has no name.
+pub fn find_symbol_definitions(query: &Query, root: Node, source_code: &str) -> SymbolDefinitions {
+    let capture_names = query.capture_names();
+    let mut definitions = HashMap::new();
+    let mut cursor = QueryCursor::new();
+
+    for query_match in cursor.matches(query, root, source_code.as_bytes()) {
+        let mut definition = None;
+        let mut name = None;
+
+        for capture in query_match.captures {
+            let capture_name = capture_names[capture.index as usize].as_str();
+
+            if capture_name.starts_with("definition.") {
+                definition = Some(capture.node);
+            } else if capture_name == "name" && name.is_none() {
+                name = capture
+                    .node
+                    .utf8_text(source_code.as_bytes())
+                    .ok()
+                    .map(String::from);
+            }
+        }
+
+        if let Some(node) = definition {
+            definitions.entry(node.id()).or_insert(name);
+        }
+    }
+
+    definitions
+}
@@ -1,28 +1,277 @@
-use crate::language::Language;
+use std::path::Path;
 
-pub enum CommentToken {
-    TripleSlashTODO,
-    DoubleSlashTODO,
-    HashTODO,
-    Other,
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+
+use crate::language::{Language, MarkerConfiguration, TestCode};
+
+/// State of a TODO comment which has not been reviewed yet
+pub const TODO_STATE: &str = "TODO";
+/// State of a TODO comment which has been reviewed
+pub const DONE_STATE: &str = "DONE";
+/// State of a TODO comment of test code, if test code is marked apart
+pub const TEST_STATE: &str = "TEST";
+
+pub const DEFAULT_MARKER_TEMPLATE: &str = "{comment} [{state}] {path}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    Comment,
+    State,
+    Path,
 }
 
-impl CommentToken {
-    pub fn from_language(language: &Language) -> Self {
-        match language {
-            Language::Rust | Language::Cpp => CommentToken::TripleSlashTODO,
-            Language::Python | Language::Ruby => CommentToken::HashTODO,
-            Language::JavaScript | Language::TypeScript => CommentToken::DoubleSlashTODO,
-            _ => CommentToken::Other,
+/// A TODO comment generated by balpan, written after the marker template of the language
+///
+/// e.g. the template `{comment} [{state}] {path}` gives `// [TODO] Foo > bar` in rust.
+pub struct Marker {
+    pieces: Vec<Piece>,
+    todo_state: String,
+    done_state: String,
+    test_state: String,
+    pattern: Regex,
+    /// Pattern of the TODO comments written with the `todo-comment-token` of the language,
+    /// if plain comments are written instead, e.g. `/// [TODO] main` in rust
+    legacy_pattern: Option<Regex>,
+}
+
+impl Marker {
+    /// Reads the marker template of the language from languages.toml
+    ///
+    /// Fails if the template is invalid, or if it needs a comment token
+    /// which the language doesn't have.
+    ///
+    /// If plain comments are written, the TODO comments written with the `todo-comment-token`
+    /// before are still parsed, so that analyzing the file again replaces them.
+    pub fn from_language(language: &Language) -> Result<Self> {
+        let configuration = language.marker_configuration();
+        let comment_token = configuration.comment_token(language);
+
+        let mut marker = Marker::new(&configuration, comment_token)
+            .with_context(|| format!("Invalid TODO comment of {}", language.as_str()))?;
+
+        let todo_comment_token = language.todo_comment_token();
+        if todo_comment_token != comment_token {
+            marker.legacy_pattern = Marker::new(&configuration, todo_comment_token)
+                .ok()
+                .map(|legacy_marker| legacy_marker.pattern);
         }
+
+        Ok(marker)
     }
 
-    pub fn to_str(&self) -> &str {
-        match self {
-            CommentToken::TripleSlashTODO => "/// [TODO]",
-            CommentToken::DoubleSlashTODO => "// [TODO]",
-            CommentToken::HashTODO => "# [TODO]",
-            CommentToken::Other => "",
+    pub fn new(configuration: &MarkerConfiguration, comment_token: Option<&str>) -> Result<Self> {
+        let template = configuration
+            .template
+            .as_deref()
+            .unwrap_or(DEFAULT_MARKER_TEMPLATE);
+        let todo_state = configuration.todo_state.as_deref().unwrap_or(TODO_STATE);
+        let done_state = configuration.done_state.as_deref().unwrap_or(DONE_STATE);
+        let test_state = configuration.test_state.as_deref().unwrap_or(TEST_STATE);
+
+        let mut pieces = vec![];
+        for piece in split_template(template)? {
+            let piece = match piece {
+                Piece::Comment => match comment_token {
+                    Some(token) => Piece::Text(token.to_string()),
+                    None => bail!("`{{comment}}` is used, but there is no comment token"),
+                },
+                piece => piece,
+            };
+
+            match (pieces.last_mut(), piece) {
+                (Some(Piece::Text(last)), Piece::Text(text)) => last.push_str(&text),
+                (_, piece) => pieces.push(piece),
+            }
         }
+
+        let states = [todo_state, done_state, test_state];
+        let is_distinct = states
+            .iter()
+            .enumerate()
+            .all(|(index, state)| !state.is_empty() && !states[..index].contains(state));
+
+        if !is_distinct {
+            bail!("TODO, DONE and TEST states must be distinct words");
+        }
+
+        let pattern = marker_pattern(&pieces, &states)?;
+
+        Ok(Marker {
+            pieces,
+            todo_state: todo_state.to_string(),
+            done_state: done_state.to_string(),
+            test_state: test_state.to_string(),
+            pattern,
+            legacy_pattern: None,
+        })
+    }
+
+    /// State of a TODO comment which has not been reviewed yet
+    pub fn todo_state(&self) -> &str {
+        &self.todo_state
+    }
+
+    /// State of a TODO comment which has been reviewed
+    pub fn done_state(&self) -> &str {
+        &self.done_state
+    }
+
+    /// State of a TODO comment of test code which has not been reviewed yet
+    pub fn test_state(&self) -> &str {
+        &self.test_state
     }
+
+    /// Writes a TODO comment, without indentation
+    pub fn render(&self, state: &str, path: &str) -> String {
+        self.pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => text.as_str(),
+                Piece::State => state,
+                Piece::Path => path,
+                Piece::Comment => "",
+            })
+            .collect()
+    }
+
+    /// Splits a TODO comment line generated by balpan into its state and symbol path
+    ///
+    /// e.g. `    // [DONE] Foo > bar` is parsed into `("DONE", "Foo > bar")`
+    pub fn parse<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        let captures = self
+            .pattern
+            .captures(line)
+            .or_else(|| self.legacy_pattern.as_ref()?.captures(line))?;
+        let state = captures.name("state")?.as_str();
+        let path = captures.name("path").map_or("", |path| path.as_str());
+
+        Some((state, path))
+    }
+}
+
+/// Patterns which find TODO comments in any language, e.g. `[TODO]` and `[DONE]`
+///
+/// Each pattern is the state along with the text stuck to it in the template.
+/// The test state is searched as well if test code is marked apart.
+pub fn search_patterns(configuration: &MarkerConfiguration) -> Vec<String> {
+    let template = configuration
+        .template
+        .as_deref()
+        .unwrap_or(DEFAULT_MARKER_TEMPLATE);
+    let mut states = vec![
+        configuration.todo_state.as_deref().unwrap_or(TODO_STATE),
+        configuration.done_state.as_deref().unwrap_or(DONE_STATE),
+    ];
+
+    if configuration.test_code == Some(TestCode::Mark) {
+        states.push(configuration.test_state.as_deref().unwrap_or(TEST_STATE));
+    }
+
+    let pieces = split_template(template).unwrap_or_default();
+    let Some(index) = pieces.iter().position(|piece| *piece == Piece::State) else {
+        return states.iter().map(|state| state.to_string()).collect();
+    };
+
+    let before = match index.checked_sub(1).map(|i| &pieces[i]) {
+        Some(Piece::Text(text)) => text.rsplit(char::is_whitespace).next().unwrap_or(""),
+        _ => "",
+    };
+    let after = match pieces.get(index + 1) {
+        Some(Piece::Text(text)) => text.split(char::is_whitespace).next().unwrap_or(""),
+        _ => "",
+    };
+
+    states
+        .iter()
+        .map(|state| format!("{}{}{}", before, state, after))
+        .collect()
+}
+
+/// Patterns which find the TODO comments of a file, written after the marker template
+/// of the language of the file
+pub fn file_search_patterns(path: &Path) -> Vec<String> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+
+    search_patterns(&Language::from_extension(extension).marker_configuration())
+}
+
+fn split_template(template: &str) -> Result<Vec<Piece>> {
+    let mut pieces = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+
+        let placeholder = match &rest[start + 1..end] {
+            "comment" => Piece::Comment,
+            "state" => Piece::State,
+            "path" => Piece::Path,
+            unknown => bail!("Unknown placeholder `{{{}}}` in `{}`", unknown, template),
+        };
+
+        if start > 0 {
+            pieces.push(Piece::Text(rest[..start].to_string()));
+        }
+        pieces.push(placeholder);
+        rest = &rest[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest.to_string()));
+    }
+
+    for required in [Piece::State, Piece::Path] {
+        if pieces.iter().filter(|piece| **piece == required).count() != 1 {
+            bail!(
+                "`{}` needs exactly one `{{state}}` and one `{{path}}`",
+                template
+            );
+        }
+    }
+
+    Ok(pieces)
+}
+
+/// Builds a regex which matches a whole TODO comment line, leading whitespaces included
+///
+/// A path at the end of the line may be omitted along with the whitespaces before it,
+/// e.g. `/// [TODO]` is matched by `{comment} [{state}] {path}`.
+fn marker_pattern(pieces: &[Piece], states: &[&str]) -> Result<Regex> {
+    let mut pattern = String::from(r"^\s*");
+
+    for (index, piece) in pieces.iter().enumerate() {
+        let is_last_path = index + 2 == pieces.len() && pieces[index + 1] == Piece::Path;
+
+        match piece {
+            Piece::Text(text) if is_last_path => {
+                let trimmed = text.trim_end();
+                pattern.push_str(&regex::escape(trimmed));
+                pattern.push_str(&format!(
+                    "(?:{}(?P<path>.*))?$",
+                    regex::escape(&text[trimmed.len()..])
+                ));
+                break;
+            }
+            Piece::Text(text) => pattern.push_str(&regex::escape(text)),
+            Piece::State => {
+                let states: Vec<String> = states.iter().map(|state| regex::escape(state)).collect();
+                pattern.push_str(&format!("(?P<state>{})", states.join("|")));
+            }
+            Piece::Path => pattern.push_str("(?P<path>.*?)"),
+            Piece::Comment => {}
+        }
+
+        if index + 1 == pieces.len() {
+            pattern.push('$');
+        }
+    }
+
+    Regex::new(&pattern).context("Could not build the pattern of TODO comments")
 }
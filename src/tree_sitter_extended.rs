@@ -1,3 +1,4 @@
+use anyhow::{anyhow, Result};
 use tree_sitter::{Node, Point, Range};
 
 pub trait MembershipCheck {
@@ -65,11 +66,57 @@ impl RangeFactory for Range {
 }
 
 pub trait ResolveSymbol {
-    fn identifier_range(&self) -> (usize, usize, usize);
+    /// Returns `(row, start column, end column)` of the name of the symbol.
+    /// `(0, 0, 0)` means the symbol is anonymous.
+    fn identifier_range(&self) -> Result<(usize, usize, usize)>;
+    fn qualifier_ranges(&self) -> Vec<(usize, usize, usize)>;
+    fn receiver_type_range(&self) -> Option<(usize, usize, usize)>;
+    fn call_target_range(&self) -> Option<(usize, usize, usize)>;
+}
+
+/// Finds the declaration wrapped by a node which is annotated as the declaration itself
+///
+/// - c++: `template <typename T>`, e.g. the `function_definition` of
+///   `template <typename T> T max(T a, T b) {}`
+/// - typescript: `declare`, e.g. `declare module "foo" {}`, and the statement
+///   of `namespace Foo {}`, which is parsed as an expression
+///
+/// Returns `None` if the node doesn't wrap a declaration.
+pub fn wrapped_declaration<'tree>(node: &Node<'tree>) -> Option<Node<'tree>> {
+    let mut declaration = *node;
+
+    loop {
+        let inner = match declaration.kind() {
+            // e.g. `template <typename T> template <typename U> void Foo<T>::bar(U u) {}`
+            "template_declaration" => {
+                let mut cursor = declaration.walk();
+                let inner = declaration
+                    .named_children(&mut cursor)
+                    .filter(|child| !child.is_extra())
+                    .filter(|child| {
+                        !matches!(child.kind(), "template_parameter_list" | "requires_clause")
+                    })
+                    .last();
+                inner
+            }
+            "ambient_declaration" => declaration.named_child(0),
+            "expression_statement" => declaration
+                .named_child(0)
+                .filter(|child| child.kind() == "internal_module"),
+            _ => None,
+        };
+
+        match inner {
+            Some(inner) => declaration = inner,
+            None => break,
+        }
+    }
+
+    (declaration != *node).then_some(declaration)
 }
 
 impl ResolveSymbol for Node<'_> {
-    fn identifier_range(&self) -> (usize, usize, usize) {
+    fn identifier_range(&self) -> Result<(usize, usize, usize)> {
         let simple_cases = [
             "attribute_item",
             "use_declaration",
@@ -79,18 +126,33 @@ impl ResolveSymbol for Node<'_> {
         ];
 
         if simple_cases.contains(&self.kind()) {
-            return (0, 0, 0);
+            return Ok((0, 0, 0));
+        }
+
+        // elixir specific code: the name of `def foo(bar) do` is the first argument of `def`
+        if self.kind() == "call" && self.call_target_range().is_some() {
+            return match call_definition_name(self) {
+                Some(name) => Ok(point_range(name)),
+                None => Ok((0, 0, 0)),
+            };
         }
 
         let mut node = self.child_by_field_name("name");
 
-        if self.kind() == "namespace_definition" && node.is_none() {
-            return (0, 0, 0);
+        // e.g. `namespace { ... }`, `typedef struct { ... } foo_t;`
+        let is_anonymous_scope = matches!(
+            self.kind(),
+            "namespace_definition" | "struct_specifier" | "union_specifier" | "enum_specifier"
+        );
+
+        if is_anonymous_scope && node.is_none() {
+            return Ok((0, 0, 0));
         }
 
-        if self.kind() == "function_definition" {
+        // e.g. `char *foo(void) {}`, `int foo(a, b) int a; char *b; {}`, `typedef int (*foo)(void);`
+        if self.kind() == "function_definition" || self.kind() == "type_definition" {
             if let Some(child) = self.child_by_field_name("declarator") {
-                node = child.child_by_field_name("declarator");
+                node = Some(innermost_declarator(child));
             }
         }
 
@@ -100,8 +162,9 @@ impl ResolveSymbol for Node<'_> {
 
         // case of decorated_definition
         if self.kind() == "decorated_definition" {
-            let definition_node = self.child_by_field_name("definition").unwrap();
-            node = definition_node.child_by_field_name("name");
+            node = self
+                .child_by_field_name("definition")
+                .and_then(|definition_node| definition_node.child_by_field_name("name"));
         }
 
         // case of impl_item
@@ -113,26 +176,216 @@ impl ResolveSymbol for Node<'_> {
             }
         }
 
-        // e.g. `export function foo() {}`
+        // e.g. `export function foo() {}`, whereas `export { foo }`, `export default foo`
+        // and `export * from "./foo"` export no declaration, so they have no name
         if self.kind() == "export_statement" {
-            // this case handles import statement especially `export * from './compiler_facade_interface';` things.
-            // I think this is not a good way to handle this case, but I don't know how to handle this case.
-            if self.child_by_field_name("source").is_some() {
-                return (0, 0, 0);
+            node = self.child_by_field_name("declaration").and_then(|child| {
+                child
+                    .child_by_field_name("name")
+                    .or_else(|| variable_name(child))
+            });
+        }
+
+        // e.g. `const foo = async () => {}`
+        if matches!(self.kind(), "lexical_declaration" | "variable_declaration") {
+            node = variable_name(*self);
+        }
+
+        // kotlin specific code: declarations don't have a `name` field
+        let is_kotlin_declaration = matches!(
+            self.kind(),
+            "class_declaration"
+                | "object_declaration"
+                | "companion_object"
+                | "function_declaration"
+        );
+
+        if node.is_none() && is_kotlin_declaration {
+            let mut cursor = self.walk();
+            node = self
+                .named_children(&mut cursor)
+                .find(|child| matches!(child.kind(), "type_identifier" | "simple_identifier"));
+
+            // e.g. `companion object {}` is named after its `companion` keyword
+            if node.is_none() && self.kind() == "companion_object" {
+                let mut cursor = self.walk();
+                node = self
+                    .children(&mut cursor)
+                    .find(|child| child.kind() == "companion");
             }
+        }
+
+        let identifier_node = node.ok_or_else(|| {
+            anyhow!(
+                "`{}` at line {} is an invalid identifier node type",
+                self.kind(),
+                self.start_position().row + 1
+            )
+        })?;
+
+        // e.g. `declare module "foo" {}` is named `foo`
+        if identifier_node.kind() == "string" {
+            let (row, from, to) = point_range(identifier_node);
+            return Ok((row, from + 1, to.saturating_sub(1).max(from + 1)));
+        }
+
+        Ok(name_range(unqualified(identifier_node)))
+    }
+
+    /// Ranges of the scopes which qualify the name of an out-of-line c++ definition
+    ///
+    /// e.g. `Foo` and `Bar` of `void Foo::Bar::baz() {}`, `Foo` of `Foo<T>::Foo() {}`
+    fn qualifier_ranges(&self) -> Vec<(usize, usize, usize)> {
+        let name = match self.kind() {
+            "function_definition" => self
+                .child_by_field_name("declarator")
+                .map(innermost_declarator),
+            _ => self.child_by_field_name("name"),
+        };
+
+        let mut ranges = vec![];
+        let mut node = name;
 
-            if let Some(child) = self.child_by_field_name("declaration") {
-                node = child.child_by_field_name("name");
+        while let Some(qualified) = node.filter(|node| node.kind() == "qualified_identifier") {
+            if let Some(scope) = qualified.child_by_field_name("scope") {
+                let scope = match scope.kind() {
+                    "template_type" => scope.child_by_field_name("name").unwrap_or(scope),
+                    _ => scope,
+                };
+                ranges.push(point_range(scope));
             }
+
+            node = qualified.child_by_field_name("name");
+        }
+
+        ranges
+    }
+
+    /// Range of the type name of a go method receiver
+    ///
+    /// e.g. `Server` of `func (s *Server) Handle() {}`
+    fn receiver_type_range(&self) -> Option<(usize, usize, usize)> {
+        if self.kind() != "method_declaration" {
+            return None;
         }
 
-        let identifier_node =
-            node.unwrap_or_else(|| panic!("`{}` is an invalid identifier node type", self.kind()));
+        let receiver = self.child_by_field_name("receiver")?;
+        let mut cursor = receiver.walk();
+        let parameter = receiver
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "parameter_declaration")?;
+
+        let mut type_node = parameter.child_by_field_name("type")?;
 
-        let from = identifier_node.start_position().column;
-        let row = identifier_node.end_position().row;
-        let to = identifier_node.end_position().column;
+        loop {
+            type_node = match type_node.kind() {
+                "pointer_type" => type_node.named_child(0)?, // func (s *Server)
+                "generic_type" => type_node.child_by_field_name("type")?, // func (s Stack[T])
+                "type_identifier" => break,
+                _ => return None,
+            };
+        }
 
-        (row, from, to)
+        Some(point_range(type_node))
     }
+
+    /// Range of the name of the function called by an elixir call
+    ///
+    /// e.g. `defmodule` of `defmodule MyApp.Accounts do ... end`
+    fn call_target_range(&self) -> Option<(usize, usize, usize)> {
+        if self.kind() != "call" {
+            return None;
+        }
+
+        let target = self.child_by_field_name("target")?;
+
+        if target.kind() != "identifier" {
+            return None;
+        }
+
+        Some(point_range(target))
+    }
+}
+
+/// Follows nested declarators of c and c++ down to the declared identifier
+fn innermost_declarator(declarator: Node) -> Node {
+    let mut node = declarator;
+
+    loop {
+        let child = match node.kind() {
+            "parenthesized_declarator" | "reference_declarator" => node.named_child(0),
+            // e.g. `operator bool() const`, whose declarator is `()`
+            "operator_cast" => None,
+            _ => node.child_by_field_name("declarator"),
+        };
+
+        match child {
+            Some(child) => node = child,
+            None => return node,
+        }
+    }
+}
+
+/// Finds the name defined by an elixir macro call such as `def` or `defmodule`
+fn call_definition_name<'tree>(call: &Node<'tree>) -> Option<Node<'tree>> {
+    let mut cursor = call.walk();
+    let arguments = call
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "arguments")?;
+    let mut node = arguments.named_child(0)?;
+
+    loop {
+        node = match node.kind() {
+            // `defmodule MyApp.Accounts do`, `def foo do`
+            "alias" | "identifier" => return Some(node),
+            // `def foo(bar) do`
+            "call" => node.child_by_field_name("target")?,
+            // `def foo(bar) when is_integer(bar) do`
+            "binary_operator" => node.child_by_field_name("left")?,
+            _ => return None,
+        };
+    }
+}
+
+/// Name of the first variable of a javascript variable declaration, e.g. `foo` of `const foo = 1`
+fn variable_name(declaration: Node) -> Option<Node> {
+    declaration
+        .named_child(0)
+        .filter(|declarator| declarator.kind() == "variable_declarator")
+        .and_then(|declarator| declarator.child_by_field_name("name"))
+}
+
+/// Strips the scopes off a qualified c++ name, e.g. `bar` of `Foo::bar`
+fn unqualified(node: Node) -> Node {
+    let mut node = node;
+
+    while node.kind() == "qualified_identifier" {
+        match node.child_by_field_name("name") {
+            Some(name) => node = name,
+            None => break,
+        }
+    }
+
+    node
+}
+
+/// Range of a name, e.g. `operator bool` of the c++ conversion operator `operator bool()`
+fn name_range(node: Node) -> (usize, usize, usize) {
+    let declarator = node.child_by_field_name("declarator");
+
+    match declarator {
+        Some(declarator) if node.kind() == "operator_cast" => {
+            let (row, from, _) = point_range(node);
+            (row, from, declarator.start_position().column)
+        }
+        _ => point_range(node),
+    }
+}
+
+fn point_range(node: Node) -> (usize, usize, usize) {
+    let from = node.start_position().column;
+    let row = node.end_position().row;
+    let to = node.end_position().column;
+
+    (row, from, to)
 }
@@ -7,6 +7,9 @@ use ignore::{DirEntry, WalkBuilder};
 use once_cell::sync::Lazy;
 use strsim::levenshtein;
 
+use crate::file_filter::{FileFilter, BALPANIGNORE_FILE_NAME};
+use crate::workspace_config_file;
+
 #[rustfmt::skip]
 static IGNORED_EXTENSIONS: Lazy<HashSet<String>> = Lazy::new(|| {
     [
@@ -38,7 +41,34 @@ pub fn get_current_repository() -> Option<Repository> {
     Some(repo)
 }
 
+/// Lists the files of the repository which balpan annotates
+///
+/// Files ignored by `.gitignore` or `.balpanignore` are skipped, along with the ones
+/// filtered out by the `[files]` table of `.balpan/config.toml`.
 pub async fn list_available_files(repo_path: &str) -> Vec<String> {
+    list_files(repo_path, &workspace_file_filter())
+}
+
+/// Lists the files of the repository which `grep` searches
+///
+/// Unlike `list_available_files`, test files are kept unless `.balpan/config.toml`
+/// excludes them explicitly, see `DEFAULT_EXCLUDE`.
+pub async fn list_searchable_files(repo_path: &str) -> Vec<String> {
+    list_files(
+        repo_path,
+        &workspace_file_filter().without_default_exclude(),
+    )
+}
+
+fn workspace_file_filter() -> FileFilter {
+    FileFilter::from_config_file(&workspace_config_file()).unwrap_or_else(|error| {
+        eprintln!("{:#}, using the default file filter", error);
+        FileFilter::default()
+    })
+}
+
+/// Lists the files under `repo_path` which are kept by `filter`
+pub fn list_files(repo_path: &str, filter: &FileFilter) -> Vec<String> {
     let mut result = Vec::new();
 
     let is_ignored = move |entry: &DirEntry| {
@@ -63,12 +93,19 @@ pub async fn list_available_files(repo_path: &str) -> Vec<String> {
         .hidden(true)
         .git_ignore(true)
         .parents(false)
+        .add_custom_ignore_filename(BALPANIGNORE_FILE_NAME)
         .filter_entry(move |f| !is_ignored(f))
         .build();
 
     for entry in walker.flatten() {
         match entry.file_type() {
             Some(file_type) if file_type.is_file() => {
+                let relative_path = entry.path().strip_prefix(repo_path).unwrap_or(entry.path());
+
+                if !filter.is_included(relative_path) {
+                    continue;
+                }
+
                 if let Ok(_file) = File::open(entry.path()) {
                     result.push(entry.path().to_string_lossy().to_string());
                 }
@@ -84,7 +121,8 @@ pub async fn list_available_files(repo_path: &str) -> Vec<String> {
 #[rustfmt::skip]
 static DICTIONARY: Lazy<Vec<&'static str>> = Lazy::new(|| {
     vec![
-        "init", "reset", "grep", "help", "file", "pattern", "format", "json", "plain",
+        "init", "reset", "grep", "help", "file", "pattern", "format", "json", "plain", "diff",
+        "strip",
     ]
 });
 
@@ -106,4 +144,22 @@ pub fn suggest_subcommand(input: &str) -> Option<String> {
     }
 
     closest
-}
\ No newline at end of file
+}
+
+/// Expands `{a,b}` of a glob pattern, e.g. `*.{js,ts}` into `*.js` and `*.ts`
+pub(crate) fn expand_braces(pattern: &str) -> Vec<String> {
+    let (Some(open), Some(close)) = (pattern.find('{'), pattern.find('}')) else {
+        return vec![pattern.to_string()];
+    };
+
+    if close < open {
+        return vec![pattern.to_string()];
+    }
+
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|alternative| expand_braces(&format!("{}{}{}", prefix, alternative, suffix)))
+        .collect()
+}
@@ -1,4 +1,5 @@
 #[cfg(test)]
 mod analyzer_test {
     mod analyze_test;
+    mod annotations_test;
 }
@@ -10,9 +10,11 @@ fn assert_analyzed_source_code(source_code: &str, expected: &str, language: &str
     let analyzer = Analyzer {
         source_code: source_code.to_string(),
         language: Language::from(language),
+        indent_unit: None,
+        marker_placement: None,
     };
 
-    let writer_queue = &analyzer.analyze();
+    let writer_queue = &analyzer.analyze().unwrap();
     let mut string_vector = vec![];
 
     for line in writer_queue {
@@ -39,7 +41,7 @@ fn test_stacked_macros() {
         }"#};
 
     let result = indoc! {r#"
-        /// [TODO] List
+        // [TODO] List
         #[derive(Deserialize)]
         #[serde(bound(deserialize = "T: Deserialize<'de>"))]
         struct List<T> {
@@ -53,7 +55,7 @@ fn test_stacked_macros() {
 #[test]
 fn test_idempotency() {
     let source_code = indoc! {r#"
-        /// [TODO] List
+        // [TODO] List
         #[derive(Deserialize)]
         #[serde(bound(deserialize = "T: Deserialize<'de>"))]
         struct List<T> {
@@ -62,7 +64,7 @@ fn test_idempotency() {
         }"#};
 
     let result = indoc! {r#"
-        /// [TODO] List
+        // [TODO] List
         #[derive(Deserialize)]
         #[serde(bound(deserialize = "T: Deserialize<'de>"))]
         struct List<T> {
@@ -162,12 +164,12 @@ fn test_ignore_todo_test_macro() {
         }"};
 
     let result = indoc! {"
-        /// [TODO] tests
+        // [TODO] tests
         #[cfg(test)]
         mod tests {
             use super::*;
 
-            /// [TODO] tests > test_foo
+            // [TODO] tests > test_foo
             #[test]
             fn test_foo() {
                 assert_eq!(foo(), 1);
@@ -186,7 +188,7 @@ fn test_ignore_doc_macro() {
         }"#};
 
     let result = indoc! {r#"
-        /// [TODO] foo
+        // [TODO] foo
         #[doc = "This is a doc comment"]
         fn foo() {
             println!("foo");
@@ -215,14 +217,14 @@ fn test_trait_and_impl() {
     }"};
 
     let result = indoc! { "
-    /// [TODO] RangeFactory
+    // [TODO] RangeFactory
     pub trait RangeFactory {
         fn from_node(node: Node) -> Range;
     }
 
-    /// [TODO] RangeFactory
+    // [TODO] RangeFactory
     impl RangeFactory for Range {
-        /// [TODO] RangeFactory > from_node
+        // [TODO] RangeFactory > from_node
         #[inline]
         fn from_node(node: Node) -> Range {
             Range {
@@ -259,16 +261,16 @@ fn test_trait_and_impl_with_mod() {
     }"};
 
     let result = indoc! { "
-    /// [TODO] tree_sitter_extended
+    // [TODO] tree_sitter_extended
     mod tree_sitter_extended {
-        /// [TODO] tree_sitter_extended > RangeFactory
+        // [TODO] tree_sitter_extended > RangeFactory
         pub trait RangeFactory {
             fn from_node(node: Node) -> Range;
         }
 
-        /// [TODO] tree_sitter_extended > RangeFactory
+        // [TODO] tree_sitter_extended > RangeFactory
         impl RangeFactory for Range {
-            /// [TODO] tree_sitter_extended > RangeFactory > from_node
+            // [TODO] tree_sitter_extended > RangeFactory > from_node
             #[inline]
             fn from_node(node: Node) -> Range {
                 Range {
@@ -283,3 +285,270 @@ fn test_trait_and_impl_with_mod() {
 
     assert_analyzed_source_code(source_code, result, "rust")
 }
+
+#[test]
+fn test_keep_done_state_of_reviewed_symbols() {
+    let source_code = indoc! {"
+        // [DONE] tree_sitter_extended
+        mod tree_sitter_extended {
+            // [TODO] tree_sitter_extended > RangeFactory
+            pub trait RangeFactory {
+                fn from_node(node: Node) -> Range;
+            }
+
+            // [DONE] tree_sitter_extended > RangeFactory
+            impl RangeFactory for Range {
+                fn from_node(node: Node) -> Range {
+                    unimplemented!()
+                }
+            }
+        }"};
+
+    let result = indoc! {"
+        // [DONE] tree_sitter_extended
+        mod tree_sitter_extended {
+            // [TODO] tree_sitter_extended > RangeFactory
+            pub trait RangeFactory {
+                fn from_node(node: Node) -> Range;
+            }
+
+            // [DONE] tree_sitter_extended > RangeFactory
+            impl RangeFactory for Range {
+                // [TODO] tree_sitter_extended > RangeFactory > from_node
+                fn from_node(node: Node) -> Range {
+                    unimplemented!()
+                }
+            }
+        }"};
+
+    assert_analyzed_source_code(source_code, result, "rust")
+}
+
+#[test]
+fn test_update_symbol_path_of_renamed_symbols() {
+    let source_code = indoc! {"
+        # [DONE] Post
+        class Article(models.Model):
+            # [TODO] Post > author
+            def writer(self):
+                return self.user"};
+
+    let result = indoc! {"
+        # [DONE] Article
+        class Article(models.Model):
+            # [TODO] Article > writer
+            def writer(self):
+                return self.user"};
+
+    assert_analyzed_source_code(source_code, result, "python")
+}
+
+#[test]
+fn test_remove_todo_comments_of_deleted_symbols() {
+    let source_code = indoc! {"
+        # [TODO] Post
+        class Post(models.Model):
+            # [DONE] Post > count
+            count = 0
+
+            # [TODO] Post > author
+            # [TODO] Post > author
+            def author(self):
+                return self.user
+
+        # [TODO] Comment"};
+
+    let result = indoc! {"
+        # [TODO] Post
+        class Post(models.Model):
+            count = 0
+
+            # [TODO] Post > author
+            def author(self):
+                return self.user
+"};
+
+    assert_analyzed_source_code(source_code, result, "python")
+}
+
+#[test]
+fn test_keep_hand_written_comments_like_todo_comments() {
+    let source_code = indoc! {"
+        # [TODO] Post
+        class Post(models.Model):
+            # [DONE] Comment > author is moved here
+            # [DONE] Post > author
+            def author(self):
+                # [DONE] Post > author checks the permissions
+                return self.user"};
+
+    assert_analyzed_source_code(source_code, source_code, "python")
+}
+
+#[test]
+fn test_keep_hand_written_todo_comments_at_top_level() {
+    let source_code = indoc! {"
+        # [TODO] drop py2 support
+
+        # [TODO] add caching
+        def foo():
+            pass"};
+
+    let result = indoc! {"
+        # [TODO] drop py2 support
+
+        # [TODO] add caching
+        # [TODO] foo
+        def foo():
+            pass"};
+
+    assert_analyzed_source_code(source_code, result, "python");
+    assert_analyzed_source_code(result, result, "python");
+}
+
+#[test]
+fn test_todo_comment_above_doc_comments() {
+    let source_code = indoc! {"
+        /// Parses the source code
+        #[inline]
+        pub fn parse() {}
+
+        /// Writes the source code
+        ///
+        /// Returns the number of written lines
+        pub fn write() -> usize {
+            0
+        }
+
+        /**
+         * Counts the lines
+         */
+        pub fn count() {}"};
+
+    let result = indoc! {"
+        // [TODO] parse
+        /// Parses the source code
+        #[inline]
+        pub fn parse() {}
+
+        // [TODO] write
+        /// Writes the source code
+        ///
+        /// Returns the number of written lines
+        pub fn write() -> usize {
+            0
+        }
+
+        // [TODO] count
+        /**
+         * Counts the lines
+         */
+        pub fn count() {}"};
+
+    assert_analyzed_source_code(source_code, result, "rust");
+    assert_analyzed_source_code(result, result, "rust");
+}
+
+#[test]
+fn test_todo_comment_above_go_doc_comments() {
+    let source_code = indoc! {r#"
+    package gin
+
+    // [DONE] Engine
+
+    // Engine is the framework's instance.
+    type Engine struct {
+    	pool sync.Pool
+    }
+
+    // Deprecated: use New instead.
+
+    // Default returns an Engine instance with the Logger attached.
+    func Default() *Engine {
+    	return New()
+    }"#};
+
+    let result = indoc! {r#"
+    package gin
+
+    // [DONE] Engine
+
+    // Engine is the framework's instance.
+    type Engine struct {
+    	pool sync.Pool
+    }
+
+    // Deprecated: use New instead.
+
+    // [TODO] Default
+
+    // Default returns an Engine instance with the Logger attached.
+    func Default() *Engine {
+    	return New()
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "go")
+}
+
+#[test]
+fn test_migrate_legacy_doc_comment_markers() {
+    let source_code = indoc! {"
+        /// Parses the source code
+        /// [DONE] parse
+        pub fn parse() {}
+
+        /// [TODO] write
+        pub fn write() {}"};
+
+    let result = indoc! {"
+        // [DONE] parse
+        /// Parses the source code
+        pub fn parse() {}
+
+        // [TODO] write
+        pub fn write() {}"};
+
+    assert_analyzed_source_code(source_code, result, "rust");
+    assert_analyzed_source_code(result, result, "rust");
+}
+
+fn annotate(source_code: &str, language: &str) -> String {
+    fetch_grammars().unwrap();
+    build_grammars(None).unwrap();
+
+    let analyzer = Analyzer {
+        source_code: source_code.to_string(),
+        language: Language::from(language),
+        indent_unit: None,
+        marker_placement: None,
+    };
+
+    analyzer.annotate().unwrap()
+}
+
+#[test]
+fn test_annotate_preserves_crlf_and_missing_final_newline() {
+    let source_code = "class Post:\r\n    def author(self):\r\n        pass  ";
+    let expected = "# [TODO] Post\r\nclass Post:\r\n    # [TODO] Post > author\r\n    def author(self):\r\n        pass  ";
+
+    assert_eq!(annotate(source_code, "python"), expected);
+    assert_eq!(annotate(expected, "python"), expected);
+}
+
+#[test]
+fn test_annotate_uses_dominant_line_ending() {
+    let source_code = "def foo():\r\n    pass\r\n\ndef bar():\r\n    pass\r\n";
+    let expected =
+        "# [TODO] foo\r\ndef foo():\r\n    pass\r\n\n# [TODO] bar\r\ndef bar():\r\n    pass\r\n";
+
+    assert_eq!(annotate(source_code, "python"), expected);
+}
+
+#[test]
+fn test_annotate_preserves_bom() {
+    let source_code = "\u{feff}fn main() {}\n";
+    let expected = "\u{feff}// [TODO] main\nfn main() {}\n";
+
+    assert_eq!(annotate(source_code, "rust"), expected);
+    assert_eq!(annotate(expected, "rust"), expected);
+}
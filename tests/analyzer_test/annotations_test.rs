@@ -0,0 +1,284 @@
+use balpan::analyzer::Analyzer;
+use balpan::grammar::{build_grammars, fetch_grammars};
+use balpan::language::{Language, MarkerPlacement};
+use indoc::indoc;
+
+fn analyzer(source_code: &str, language: &str) -> Analyzer {
+    fetch_grammars().unwrap();
+    build_grammars(None).unwrap();
+
+    Analyzer {
+        source_code: source_code.to_string(),
+        language: Language::from(language),
+        indent_unit: None,
+        marker_placement: None,
+    }
+}
+
+#[test]
+fn test_annotations_of_nested_symbols() {
+    let source_code = indoc! {"
+        mod tree_sitter_extended {
+            pub trait RangeFactory {
+                fn from_node(node: Node) -> Range;
+            }
+
+            impl RangeFactory for Range {
+                #[inline]
+                fn from_node(node: Node) -> Range {
+                    unimplemented!()
+                }
+            }
+        }"};
+
+    let annotations = analyzer(source_code, "rust").annotations().unwrap();
+
+    let summary: Vec<(String, &str, usize, usize, usize)> = annotations
+        .iter()
+        .map(|annotation| {
+            (
+                annotation.symbol_path.join(" > "),
+                annotation.kind.as_str(),
+                annotation.target_line,
+                annotation.line,
+                annotation.depth,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("tree_sitter_extended".to_string(), "mod_item", 0, 0, 0),
+            (
+                "tree_sitter_extended > RangeFactory".to_string(),
+                "trait_item",
+                1,
+                2,
+                1
+            ),
+            (
+                "tree_sitter_extended > RangeFactory".to_string(),
+                "impl_item",
+                5,
+                7,
+                1
+            ),
+            (
+                "tree_sitter_extended > RangeFactory > from_node".to_string(),
+                "function_item",
+                6,
+                9,
+                2
+            ),
+        ]
+    );
+
+    let function = &annotations[3];
+    assert_eq!(function.range.start_point.row, 7);
+    assert_eq!(function.range.end_point.row, 9);
+}
+
+#[test]
+fn test_annotations_point_to_existing_todo_comments() {
+    let source_code = indoc! {"
+        # [TODO] Post
+        class Post(models.Model):
+            def author(self):
+                return self.user"};
+
+    let analyzer = analyzer(source_code, "python");
+    let annotations = analyzer.annotations().unwrap();
+
+    assert_eq!(annotations.len(), 2);
+    assert_eq!(annotations[0].line, 0);
+    assert_eq!(annotations[1].symbol_path, vec!["Post", "author"]);
+    assert_eq!(annotations[1].line, 2);
+
+    let analyzed: Vec<String> = analyzer.analyze().unwrap().into_iter().collect();
+    assert_eq!(analyzed[annotations[1].line], "    # [TODO] Post > author");
+}
+
+#[test]
+fn test_annotations_of_test_code() {
+    let source_code = indoc! {"
+        fn attestation() {}
+
+        #[cfg(test)]
+        mod tests {
+            #[test]
+            fn test_attestation() {}
+        }"};
+
+    let annotations = analyzer(source_code, "rust").annotations().unwrap();
+    let summary: Vec<(String, bool)> = annotations
+        .iter()
+        .map(|annotation| (annotation.symbol_path.join(" > "), annotation.is_test))
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            ("attestation".to_string(), false),
+            ("tests".to_string(), true),
+            ("tests > test_attestation".to_string(), true),
+        ]
+    );
+
+    let source_code = indoc! {"
+        class PostTest(unittest.TestCase):
+            def setUp(self):
+                pass
+
+        def test_post():
+            pass
+
+        def testimony():
+            pass"};
+
+    let annotations = analyzer(source_code, "python").annotations().unwrap();
+    let is_test: Vec<bool> = annotations
+        .iter()
+        .map(|annotation| annotation.is_test)
+        .collect();
+
+    assert_eq!(is_test, vec![true, true, true, false]);
+}
+
+#[test]
+fn test_annotations_of_test_calls() {
+    let source_code = indoc! {r#"
+        function parse(input) {}
+
+        describe("parse", () => {
+          it("parses numbers", () => {});
+        });"#};
+
+    let annotations = analyzer(source_code, "javascript").annotations().unwrap();
+
+    assert_eq!(annotations.len(), 3);
+    assert_eq!(annotations[1].symbol_path, vec!["parse"]);
+    assert_eq!(annotations[1].kind, "expression_statement");
+    assert_eq!(annotations[2].symbol_path, vec!["parse", "parses numbers"]);
+    assert!(!annotations[0].is_test);
+    assert!(annotations[1].is_test);
+    assert!(annotations[2].is_test);
+
+    let source_code = indoc! {r#"
+        describe Post do
+          it "has an author" do
+            expect(post.author).to eq(user)
+          end
+        end"#};
+
+    let annotations = analyzer(source_code, "ruby").annotations().unwrap();
+    let paths: Vec<String> = annotations
+        .iter()
+        .map(|annotation| annotation.symbol_path.join(" > "))
+        .collect();
+
+    assert_eq!(paths, vec!["Post", "Post > has an author"]);
+}
+
+fn analyze_with_placement(source_code: &str, language: &str, placement: MarkerPlacement) -> String {
+    let analyzer = Analyzer {
+        marker_placement: Some(placement),
+        ..analyzer(source_code, language)
+    };
+
+    Vec::from(analyzer.analyze().unwrap()).join("\n")
+}
+
+#[test]
+fn test_start_of_body_placement() {
+    let source_code = indoc! {r#"
+        def parse(source):
+            """Parses the source code"""
+            tree = build(source)
+            return tree
+
+        def noop(): pass"#};
+
+    let expected = indoc! {r#"
+        def parse(source):
+            """Parses the source code"""
+            # [TODO] parse
+            tree = build(source)
+            return tree
+
+        # [TODO] noop
+        def noop(): pass"#};
+
+    let placement = MarkerPlacement::StartOfBody;
+    assert_eq!(
+        analyze_with_placement(source_code, "python", placement),
+        expected
+    );
+    assert_eq!(
+        analyze_with_placement(expected, "python", placement),
+        expected
+    );
+}
+
+#[test]
+fn test_above_declaration_placement() {
+    let source_code = indoc! {r#"
+        @app.route("/")
+        @login_required
+        def index():
+            pass"#};
+
+    let expected = indoc! {r#"
+        @app.route("/")
+        @login_required
+        # [TODO] index
+        def index():
+            pass"#};
+
+    let placement = MarkerPlacement::AboveDeclaration;
+    assert_eq!(
+        analyze_with_placement(source_code, "python", placement),
+        expected
+    );
+    assert_eq!(
+        analyze_with_placement(expected, "python", placement),
+        expected
+    );
+
+    let source_code = indoc! {r#"
+        #[derive(Debug)]
+        #[serde(rename_all = "kebab-case")]
+        struct Config {
+            name: String,
+        }"#};
+
+    let expected = indoc! {r#"
+        #[derive(Debug)]
+        #[serde(rename_all = "kebab-case")]
+        // [TODO] Config
+        struct Config {
+            name: String,
+        }"#};
+
+    assert_eq!(
+        analyze_with_placement(source_code, "rust", placement),
+        expected
+    );
+    assert_eq!(
+        analyze_with_placement(expected, "rust", placement),
+        expected
+    );
+}
+
+#[test]
+fn test_analyze_without_parser_returns_error() {
+    let analyzer = Analyzer {
+        source_code: "print 'hello'".to_string(),
+        language: Language::from("brainfuck"),
+        indent_unit: None,
+        marker_placement: None,
+    };
+
+    assert!(analyzer.analyze().is_err());
+    assert!(analyzer.annotations().is_err());
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod diff_test {
+    use std::path::Path;
+
+    use balpan::commands::diff::{diff_lines, unified_diff, DiffReport, Edit};
+    use indoc::indoc;
+
+    #[test]
+    fn test_diff_lines_with_insertions() {
+        let old = vec!["a", "b", "c"];
+        let new = vec!["x", "a", "b", "y", "c"];
+
+        let expected = vec![
+            Edit::Insert { new: 0 },
+            Edit::Equal { old: 0, new: 1 },
+            Edit::Equal { old: 1, new: 2 },
+            Edit::Insert { new: 3 },
+            Edit::Equal { old: 2, new: 4 },
+        ];
+
+        assert_eq!(diff_lines(&old, &new), expected);
+    }
+
+    #[test]
+    fn test_diff_lines_with_deletion() {
+        let old = vec!["a", "b", "c"];
+        let new = vec!["a", "c"];
+
+        let expected = vec![
+            Edit::Equal { old: 0, new: 0 },
+            Edit::Delete { old: 1 },
+            Edit::Equal { old: 2, new: 1 },
+        ];
+
+        assert_eq!(diff_lines(&old, &new), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_of_same_file_is_empty() {
+        let source_code = "fn main() {}\n";
+
+        assert_eq!(unified_diff("main.rs", source_code, source_code), "");
+    }
+
+    #[test]
+    fn test_unified_diff_of_inserted_todo_comment() {
+        let original = indoc! {"
+            use std::io;
+
+            fn main() {
+                println!(\"hello\");
+            }
+        "};
+
+        let annotated = indoc! {"
+            use std::io;
+
+            /// [TODO] main
+            fn main() {
+                println!(\"hello\");
+            }
+        "};
+
+        let expected = [
+            "--- a/src/main.rs",
+            "+++ b/src/main.rs",
+            "@@ -1,5 +1,6 @@",
+            " use std::io;",
+            " ",
+            "+/// [TODO] main",
+            " fn main() {",
+            "     println!(\"hello\");",
+            " }",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(unified_diff("src/main.rs", original, annotated), expected);
+    }
+
+    #[test]
+    fn test_report_lists_inserted_lines() {
+        let original = "class Foo:\n    def bar(self):\n        pass\n";
+        let annotated =
+            "# [TODO] Foo\nclass Foo:\n    # [TODO] Foo > bar\n    def bar(self):\n        pass\n";
+
+        let mut report = DiffReport::new();
+        report.add_file(Path::new("foo.py"), original, annotated);
+        report.add_file(Path::new("unchanged.py"), original, original);
+
+        assert_eq!(report.files.len(), 1);
+
+        let markers = &report.files[0].markers;
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[0].line, 1);
+        assert_eq!(markers[0].content, "# [TODO] Foo");
+        assert_eq!(markers[1].line, 3);
+        assert_eq!(markers[1].content, "    # [TODO] Foo > bar");
+        assert!(report.files[0].removed_markers.is_empty());
+    }
+
+    #[test]
+    fn test_report_names_files_relative_to_root() {
+        let original = "def foo():\n    pass\n";
+        let annotated = "# [TODO] foo\ndef foo():\n    pass\n";
+
+        let mut report = DiffReport::with_root(Path::new("/repo"));
+        report.add_file(Path::new("/repo/src/foo.py"), original, annotated);
+
+        assert_eq!(report.files[0].name, "src/foo.py");
+        assert!(report
+            .report_formatting(None)
+            .starts_with("--- a/src/foo.py\n+++ b/src/foo.py\n"));
+    }
+
+    #[test]
+    fn test_report_lists_removed_lines() {
+        let original = "# [DONE] foo\ndef bar():\n    pass\n\n# [TODO] baz\n";
+        let annotated = "# [DONE] bar\ndef bar():\n    pass\n\n";
+
+        let mut report = DiffReport::new();
+        report.add_file(Path::new("foo.py"), original, annotated);
+
+        let file = &report.files[0];
+        assert_eq!(file.markers.len(), 1);
+        assert_eq!(file.markers[0].line, 1);
+        assert_eq!(file.markers[0].content, "# [DONE] bar");
+
+        assert_eq!(file.removed_markers.len(), 2);
+        assert_eq!(file.removed_markers[0].line, 1);
+        assert_eq!(file.removed_markers[0].content, "# [DONE] foo");
+        assert_eq!(file.removed_markers[1].line, 5);
+        assert_eq!(file.removed_markers[1].content, "# [TODO] baz");
+    }
+}
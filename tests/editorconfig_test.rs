@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod editorconfig_test {
+    use std::fs;
+    use std::path::Path;
+
+    use balpan::editorconfig::EditorConfig;
+    use indoc::indoc;
+
+    const EDITORCONFIG: &str = indoc! {"
+        root = true
+
+        [*]
+        indent_style = space
+        indent_size = 4
+
+        # go files are indented with tabs
+        [*.go]
+        indent_style = tab
+
+        [*.{js,ts}]
+        indent_size = 2
+
+        [Makefile]
+        indent_style = tab
+    "};
+
+    fn indent_unit_of(path: &str) -> Option<String> {
+        let mut config = EditorConfig::default();
+        config.apply(EDITORCONFIG, Path::new(path));
+
+        config.indent_unit()
+    }
+
+    #[test]
+    fn test_later_sections_take_precedence() {
+        assert_eq!(indent_unit_of("src/main.rs"), Some("    ".to_string()));
+        assert_eq!(indent_unit_of("gin.go"), Some("\t".to_string()));
+        assert_eq!(indent_unit_of("src/index.ts"), Some("  ".to_string()));
+        assert_eq!(indent_unit_of("web/app.js"), Some("  ".to_string()));
+    }
+
+    #[test]
+    fn test_indent_size_of_tab_uses_tab_width() {
+        let mut config = EditorConfig::default();
+        config.apply(
+            indoc! {"
+                [*.c]
+                indent_style = space
+                indent_size = tab
+                tab_width = 8
+            "},
+            Path::new("main.c"),
+        );
+
+        assert_eq!(config.indent_unit(), Some("        ".to_string()));
+    }
+
+    #[test]
+    fn test_no_indent_unit_without_indent_style() {
+        let mut config = EditorConfig::default();
+        config.apply("[*.py]\nindent_size = 4\n", Path::new("main.py"));
+
+        assert_eq!(config.indent_unit(), None);
+    }
+
+    #[test]
+    fn test_resolve_stops_at_root_editorconfig() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        fs::create_dir_all(project.join("src")).unwrap();
+
+        fs::write(
+            dir.path().join(".editorconfig"),
+            "[*]\nindent_style = tab\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join(".editorconfig"),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 2\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join("src").join(".editorconfig"),
+            "[*.ts]\nindent_size = 4\n",
+        )
+        .unwrap();
+
+        let path = project.join("src").join("index.ts");
+        fs::write(&path, "").unwrap();
+
+        assert_eq!(
+            EditorConfig::resolve(&path).indent_unit(),
+            Some("    ".to_string())
+        );
+    }
+}
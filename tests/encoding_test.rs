@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod encoding_test {
+    use balpan::encoding::DecodedSource;
+
+    #[test]
+    fn test_decode_utf8() {
+        let source = DecodedSource::decode("fn main() {}\n".as_bytes(), None).unwrap();
+
+        assert!(source.is_utf8());
+        assert_eq!(source.text, "fn main() {}\n");
+    }
+
+    #[test]
+    fn test_decode_keeps_bom() {
+        let bytes = b"\xef\xbb\xbfdef main():\r\n    pass";
+        let source = DecodedSource::decode(bytes, Some("latin1")).unwrap();
+
+        assert!(source.is_utf8());
+        assert_eq!(source.text, "\u{feff}def main():\r\n    pass");
+        assert_eq!(source.encode(&source.text).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_decode_utf16_with_bom() {
+        let text = "\u{feff}# caf\u{e9}\n";
+        let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let source = DecodedSource::decode(&bytes, None).unwrap();
+
+        assert_eq!(source.text, text);
+        assert_eq!(
+            source.encode("\u{feff}# [TODO] main\n").unwrap(),
+            "\u{feff}# [TODO] main\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<u8>>()
+        );
+    }
+
+    #[test]
+    fn test_decode_with_charset() {
+        // `# 日本語` in Shift-JIS
+        let bytes = b"# \x93\xfa\x96\x7b\x8c\xea\ndef main():\n    pass\n";
+        let source = DecodedSource::decode(bytes, Some("shift_jis")).unwrap();
+
+        assert!(!source.is_utf8());
+        assert_eq!(source.text, "# 日本語\ndef main():\n    pass\n");
+
+        let annotated = format!("# [TODO] main\n{}", source.text);
+        let mut expected = b"# [TODO] main\n".to_vec();
+        expected.extend_from_slice(bytes);
+
+        assert_eq!(source.encode(&annotated).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_detect_non_utf8_encoding() {
+        let bytes = b"// Cr\xe9e un caf\xe9 na\xefve pour le r\xe9sum\xe9\nint main(void) {}\n";
+        let source = DecodedSource::decode(bytes, None).unwrap();
+
+        assert!(!source.is_utf8());
+        assert_eq!(source.encode(&source.text).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_invalid_bytes_for_charset() {
+        let bytes = b"# caf\xe9\n";
+
+        assert!(DecodedSource::decode(bytes, Some("utf-8")).is_err());
+        assert!(DecodedSource::decode(bytes, Some("klingon")).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod file_filter_test {
+    use std::fs;
+    use std::path::Path;
+
+    use balpan::file_filter::{FileFilter, FilesConfiguration};
+    use balpan::utils::list_files;
+    use indoc::indoc;
+
+    #[test]
+    fn test_default_filter_skips_tests_only() {
+        let filter = FileFilter::default();
+
+        assert!(filter.is_included(Path::new("src/attestation.rs")));
+        assert!(filter.is_included(Path::new("contest/main.py")));
+        assert!(!filter.is_included(Path::new("tests/analyzer_test.rs")));
+        assert!(!filter.is_included(Path::new("src/parser/tests/mod.rs")));
+        assert!(!filter.is_included(Path::new("src/parser_test.go")));
+        assert!(!filter.is_included(Path::new("test_views.py")));
+        assert!(!filter.is_included(Path::new("web/app.spec.ts")));
+    }
+
+    #[test]
+    fn test_include_and_exclude_patterns() {
+        let filter = FileFilter::from_toml(indoc! {r#"
+            [files]
+            include = ["src/**", "lib.{rs,py}"]
+            exclude = ["src/generated/**"]
+        "#})
+        .unwrap();
+
+        assert!(filter.is_included(Path::new("src/main.rs")));
+        assert!(filter.is_included(Path::new("src/tests/mod.rs")));
+        assert!(filter.is_included(Path::new("python/lib.py")));
+        assert!(!filter.is_included(Path::new("src/generated/schema.rs")));
+        assert!(!filter.is_included(Path::new("benches/bench.rs")));
+    }
+
+    #[test]
+    fn test_empty_exclude_keeps_tests() {
+        let configuration = FilesConfiguration {
+            exclude: Some(vec![]),
+            ..Default::default()
+        };
+        let filter = FileFilter::new(&configuration).unwrap();
+
+        assert!(filter.is_included(Path::new("tests/analyzer_test.rs")));
+    }
+
+    #[test]
+    fn test_search_keeps_tests_but_not_configured_excludes() {
+        let filter = FileFilter::default().without_default_exclude();
+
+        assert!(filter.is_included(Path::new("tests/analyzer_test.rs")));
+        assert!(filter.is_included(Path::new("src/parser_test.go")));
+
+        let filter = FileFilter::from_toml("[files]\nexclude = [\"*_test.*\"]")
+            .unwrap()
+            .without_default_exclude();
+
+        assert!(filter.is_included(Path::new("tests/fixtures/post.py")));
+        assert!(!filter.is_included(Path::new("src/parser_test.go")));
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        assert!(FileFilter::from_toml("[files]\ninclude = [\"src/***\"]").is_err());
+        assert!(FileFilter::from_toml("[files]\ninclude = \"src/**\"").is_err());
+        assert!(FileFilter::from_toml("").is_ok());
+    }
+
+    #[test]
+    fn test_list_files_with_balpanignore() {
+        let dir = tempfile::Builder::new().prefix("balpan").tempdir().unwrap();
+        let root = dir.path();
+
+        fs::create_dir_all(root.join("src/vendor")).unwrap();
+        fs::create_dir_all(root.join("tests")).unwrap();
+        fs::write(root.join(".balpanignore"), "vendor/\n").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("src/vendor/lib.rs"), "fn lib() {}\n").unwrap();
+        fs::write(root.join("tests/main_test.rs"), "fn test() {}\n").unwrap();
+
+        let files = list_files(root.to_str().unwrap(), &FileFilter::default());
+        let expected = root.join("src/main.rs").to_string_lossy().to_string();
+
+        assert_eq!(files, vec![expected]);
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod integration_test {
     use balpan::analyzer::Analyzer;
+    use balpan::commands::strip::strip_todo_comments;
     use balpan::grammar::{build_grammars, fetch_grammars};
     use balpan::language::Language;
 
@@ -14,9 +15,11 @@ mod integration_test {
         let analyzer = Analyzer {
             source_code: source_code.to_string(),
             language: Language::from(language),
+            indent_unit: None,
+            marker_placement: None,
         };
 
-        let writer_queue = &analyzer.analyze();
+        let writer_queue = &analyzer.analyze().unwrap();
         let mut string_vector = vec![];
 
         for line in writer_queue {
@@ -31,5 +34,9 @@ mod integration_test {
         }
 
         assert_eq!(expected, actual);
+
+        // stripping the TODO comments should give back the original source code
+        let stripped = strip_todo_comments(&actual, &analyzer.language).unwrap();
+        assert_eq!(source_code, stripped);
     }
 }
@@ -18,3 +18,21 @@ mod typescript_test;
 
 #[cfg(test)]
 mod javascript_test;
+
+#[cfg(test)]
+mod go_test;
+
+#[cfg(test)]
+mod java_test;
+
+#[cfg(test)]
+mod kotlin_test;
+
+#[cfg(test)]
+mod csharp_test;
+
+#[cfg(test)]
+mod php_test;
+
+#[cfg(test)]
+mod elixir_test;
@@ -6,3 +6,6 @@ mod redis_case_test;
 
 #[cfg(test)]
 mod nginx_case_test;
+
+#[cfg(test)]
+mod sqlite_case_test;
@@ -22,7 +22,7 @@ fn test_function_definition() {
     }"#};
 
     let result = indoc! { r#"
-    /// [TODO] object_as_optval
+    // [TODO] object_as_optval
     static OptVal object_as_optval(Object o, bool *error)
     {
       switch (o.type) {
@@ -40,7 +40,7 @@ fn test_function_definition() {
       }
     }"#};
 
-    assert_analyzed_source_code(source_code, result, "cpp");
+    assert_analyzed_source_code(source_code, result, "c");
 }
 
 #[test]
@@ -62,7 +62,7 @@ fn test_function_definition_with_conditional_compilation() {
     }"#};
 
     let result = indoc! { r#"
-    /// [TODO] path_is_absolute
+    // [TODO] path_is_absolute
     int path_is_absolute(const char *fname)
     {
     #ifdef MSWIN
@@ -78,5 +78,5 @@ fn test_function_definition_with_conditional_compilation() {
     #endif
     }"#};
 
-    assert_analyzed_source_code(source_code, result, "cpp");
+    assert_analyzed_source_code(source_code, result, "c");
 }
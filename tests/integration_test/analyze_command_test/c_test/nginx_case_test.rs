@@ -40,7 +40,7 @@ fn test_function_definition_with_nginx_convention() {
     }"#};
 
     let result = indoc! { r#"
-    /// [TODO] ngx_stream_ssl_alpn_select
+    // [TODO] ngx_stream_ssl_alpn_select
     static int
     ngx_stream_ssl_alpn_select(ngx_ssl_conn_t *ssl_conn, const unsigned char **out,
         unsigned char *outlen, const unsigned char *in, unsigned int inlen,
@@ -76,5 +76,5 @@ fn test_function_definition_with_nginx_convention() {
         return SSL_TLSEXT_ERR_OK;
     }"#};
 
-    assert_analyzed_source_code(source_code, result, "cpp");
+    assert_analyzed_source_code(source_code, result, "c");
 }
@@ -51,7 +51,7 @@ fn test_declaration_of_function() {
     void listLinkNodeTail(list *list, listNode *node);
     void listUnlinkNode(list *list, listNode *node);"#};
 
-    assert_analyzed_source_code(source_code, result, "cpp");
+    assert_analyzed_source_code(source_code, result, "c");
 }
 
 #[ignore]
@@ -85,7 +85,7 @@ fn test_function_definition_together_with_macro_combined() {
     }"#};
 
     let result = indoc! {r#"
-    /// [TODO] clusterCreatePublishMsgBlock
+    // [TODO] clusterCreatePublishMsgBlock
     REDIS_NO_SANITIZE("bounds")
     clusterMsgSendBlock *clusterCreatePublishMsgBlock(robj *channel, robj *message, uint16_t type) {
 
@@ -113,5 +113,5 @@ fn test_function_definition_together_with_macro_combined() {
         return msgblock;
     }"#};
 
-    assert_analyzed_source_code(source_code, result, "cpp");
+    assert_analyzed_source_code(source_code, result, "c");
 }
@@ -0,0 +1,91 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_struct_union_and_enum_specifiers() {
+    let source_code = indoc! { r#"
+    struct sqlite3_vfs;
+
+    struct Mem {
+      union MemValue {
+        double r;
+        i64 i;
+      } u;
+      u16 flags;
+    };
+
+    union Aux {
+      void *p;
+      int n;
+    };
+
+    enum PagerState {
+      PAGER_OPEN,
+      PAGER_READER
+    };
+
+    typedef struct Vdbe Vdbe;
+    typedef int (*sqlite3_callback)(void*,int,char**, char**);"#};
+
+    let result = indoc! { r#"
+    struct sqlite3_vfs;
+
+    // [TODO] Mem
+    struct Mem {
+      union MemValue {
+        double r;
+        i64 i;
+      } u;
+      u16 flags;
+    };
+
+    // [TODO] Aux
+    union Aux {
+      void *p;
+      int n;
+    };
+
+    // [TODO] PagerState
+    enum PagerState {
+      PAGER_OPEN,
+      PAGER_READER
+    };
+
+    // [TODO] Vdbe
+    typedef struct Vdbe Vdbe;
+    // [TODO] sqlite3_callback
+    typedef int (*sqlite3_callback)(void*,int,char**, char**);"#};
+
+    assert_analyzed_source_code(source_code, result, "c");
+}
+
+#[test]
+fn test_function_definitions_with_declarators() {
+    let source_code = indoc! { r#"
+    static char *sqlite3StrDup(const char *z){
+      return z ? strdup(z) : 0;
+    }
+
+    int sqlite3_strglob(zGlob, zStr)
+      const char *zGlob;
+      const char *zStr;
+    {
+      return patternCompare(zGlob, zStr);
+    }"#};
+
+    let result = indoc! { r#"
+    // [TODO] sqlite3StrDup
+    static char *sqlite3StrDup(const char *z){
+      return z ? strdup(z) : 0;
+    }
+
+    // [TODO] sqlite3_strglob
+    int sqlite3_strglob(zGlob, zStr)
+      const char *zGlob;
+      const char *zStr;
+    {
+      return patternCompare(zGlob, zStr);
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "c");
+}
@@ -4,20 +4,20 @@ use indoc::indoc;
 #[test]
 fn test_function_definition_with_nested_scope() {
     let result = indoc! { r#"
-    /// [TODO] BloombergLP
+    // [TODO] BloombergLP
     namespace BloombergLP {
-        /// [TODO] BloombergLP > bmqimp
+        // [TODO] BloombergLP > bmqimp
         namespace bmqimp {
-            /// [TODO] BloombergLP > bmqimp > anonymous
+            // [TODO] BloombergLP > bmqimp > anonymous
             namespace {
                 // CONSTANTS
                 const double             k_RECONNECT_INTERVAL_MS = 500;
                 const int                k_RECONNECT_COUNT = bsl::numeric_limits<int>::max();
                 const bsls::Types::Int64 k_CHANNEL_LOW_WATERMARK = 512 * 1024;
 
+                // [TODO] BloombergLP > bmqimp > anonymous > statContextConfiguration
                 /// Create the StatContextConfiguration to use, from the specified
                 /// `options`, and using the specified `allocator` for memory allocations.
-                /// [TODO] BloombergLP > bmqimp > anonymous > statContextConfiguration
                 mwcst::StatContextConfiguration
                 statContextConfiguration(const bmqt::SessionOptions& options,
                                          bslma::Allocator*           allocator)
@@ -103,9 +103,9 @@ fn test_class_declaration_with_nested_scope() {
     }"#};
 
     let result = indoc! { r#"
-    /// [TODO] m_bmqbrkr
+    // [TODO] m_bmqbrkr
     namespace m_bmqbrkr {
-        /// [TODO] m_bmqbrkr > Task_AllocatorManager
+        // [TODO] m_bmqbrkr > Task_AllocatorManager
         class Task_AllocatorManager {
           private:
             mqbcfg::AllocatorType::Value d_type;
@@ -204,3 +204,222 @@ fn test_templated_function_definition() {
 
     assert_analyzed_source_code(source_code, result, "cpp");
 }
+
+#[test]
+fn test_templated_member_function_definitions() {
+    let source_code = indoc! { r#"
+    template <typename CMD>
+    bool parseCommand(CMD* command, const bsl::string& jsonInput)
+    {
+        bsl::istringstream     is(jsonInput);
+        baljsn::DecoderOptions options;
+        options.setSkipUnknownElements(true);
+        baljsn::Decoder decoder;
+        int             rc = decoder.decode(is, command, options);
+        if (rc != 0) {
+            BALL_LOG_ERROR << "Unable to decode: " << jsonInput << bsl::endl
+                           << decoder.loggedMessages();
+            return false;  // RETURN
+        }
+
+        return true;
+    }
+
+    template <typename TYPE>
+    inline bool Value::is() const
+    {
+        return d_value.is<TYPE>();
+    }
+
+    template <typename TYPE>
+    inline const TYPE& Value::the() const
+    {
+        return d_value.the<TYPE>();
+    }
+
+    template <class VISITOR>
+    inline typename VISITOR::ResultType Value::apply(const VISITOR& visitor) const
+    {
+        return d_value.apply(visitor);
+    }"#};
+
+    let result = indoc! { r#"
+    // [TODO] parseCommand
+    template <typename CMD>
+    bool parseCommand(CMD* command, const bsl::string& jsonInput)
+    {
+        bsl::istringstream     is(jsonInput);
+        baljsn::DecoderOptions options;
+        options.setSkipUnknownElements(true);
+        baljsn::Decoder decoder;
+        int             rc = decoder.decode(is, command, options);
+        if (rc != 0) {
+            BALL_LOG_ERROR << "Unable to decode: " << jsonInput << bsl::endl
+                           << decoder.loggedMessages();
+            return false;  // RETURN
+        }
+
+        return true;
+    }
+
+    // [TODO] Value > is
+    template <typename TYPE>
+    inline bool Value::is() const
+    {
+        return d_value.is<TYPE>();
+    }
+
+    // [TODO] Value > the
+    template <typename TYPE>
+    inline const TYPE& Value::the() const
+    {
+        return d_value.the<TYPE>();
+    }
+
+    // [TODO] Value > apply
+    template <class VISITOR>
+    inline typename VISITOR::ResultType Value::apply(const VISITOR& visitor) const
+    {
+        return d_value.apply(visitor);
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "cpp");
+}
+
+#[test]
+fn test_out_of_line_member_definitions() {
+    let source_code = indoc! { r#"
+    namespace mqbblp {
+        Cluster::Cluster(const bsl::string& name)
+        : d_name(name)
+        {
+        }
+
+        Cluster::~Cluster()
+        {
+        }
+
+        bool Cluster::operator==(const Cluster& other) const
+        {
+            return d_name == other.d_name;
+        }
+
+        Cluster::operator bool() const
+        {
+            return !d_name.empty();
+        }
+
+        void ClusterState::Observer::onPartitionPrimaryAssignment(int partitionId)
+        {
+        }
+    }"#};
+
+    let result = indoc! { r#"
+    // [TODO] mqbblp
+    namespace mqbblp {
+        // [TODO] mqbblp > Cluster > Cluster
+        Cluster::Cluster(const bsl::string& name)
+        : d_name(name)
+        {
+        }
+
+        // [TODO] mqbblp > Cluster > ~Cluster
+        Cluster::~Cluster()
+        {
+        }
+
+        // [TODO] mqbblp > Cluster > operator==
+        bool Cluster::operator==(const Cluster& other) const
+        {
+            return d_name == other.d_name;
+        }
+
+        // [TODO] mqbblp > Cluster > operator bool
+        Cluster::operator bool() const
+        {
+            return !d_name.empty();
+        }
+
+        // [TODO] mqbblp > ClusterState > Observer > onPartitionPrimaryAssignment
+        void ClusterState::Observer::onPartitionPrimaryAssignment(int partitionId)
+        {
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "cpp");
+}
+
+#[test]
+fn test_templated_class_struct_and_enum() {
+    let source_code = indoc! { r#"
+    template <class TYPE>
+    class ObjectPool {
+      public:
+        TYPE* getObject() { return d_pool.get(); }
+    };
+
+    struct Message {
+        int d_id;
+
+        bool isValid() const { return d_id > 0; }
+    };
+
+    struct Forward;
+
+    enum class Status { e_SUCCESS, e_FAILURE };"#};
+
+    let result = indoc! { r#"
+    // [TODO] ObjectPool
+    template <class TYPE>
+    class ObjectPool {
+      public:
+        // [TODO] ObjectPool > getObject
+        TYPE* getObject() { return d_pool.get(); }
+    };
+
+    // [TODO] Message
+    struct Message {
+        int d_id;
+
+        // [TODO] Message > isValid
+        bool isValid() const { return d_id > 0; }
+    };
+
+    struct Forward;
+
+    // [TODO] Status
+    enum class Status { e_SUCCESS, e_FAILURE };"#};
+
+    assert_analyzed_source_code(source_code, result, "cpp");
+}
+
+#[test]
+fn test_extern_c_block() {
+    let source_code = indoc! { r#"
+    extern "C" {
+        int bmq_init(void)
+        {
+            return 0;
+        }
+    }
+
+    extern "C" void bmq_shutdown(void)
+    {
+    }"#};
+
+    let result = indoc! { r#"
+    extern "C" {
+        // [TODO] bmq_init
+        int bmq_init(void)
+        {
+            return 0;
+        }
+    }
+
+    // [TODO] bmq_shutdown
+    extern "C" void bmq_shutdown(void)
+    {
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "cpp");
+}
@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod aspnetcore_case_test;
@@ -0,0 +1,118 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_file_scoped_namespace_with_attributes() {
+    let source_code = indoc! {r#"
+    using Microsoft.AspNetCore.Mvc;
+
+    namespace MvcSandbox.Controllers;
+
+    [ApiController]
+    [Route("[controller]")]
+    public partial class HomeController : Controller
+    {
+        private readonly ILogger _logger;
+
+        public HomeController(ILogger logger)
+        {
+            _logger = logger;
+        }
+
+        public string Title { get; set; }
+
+        [HttpGet]
+        public IActionResult Index()
+        {
+            return View();
+        }
+    }"#};
+
+    let result = indoc! {r#"
+    using Microsoft.AspNetCore.Mvc;
+
+    // [TODO] MvcSandbox.Controllers
+    namespace MvcSandbox.Controllers;
+
+    // [TODO] MvcSandbox.Controllers > HomeController
+    [ApiController]
+    [Route("[controller]")]
+    public partial class HomeController : Controller
+    {
+        private readonly ILogger _logger;
+
+        // [TODO] MvcSandbox.Controllers > HomeController > HomeController
+        public HomeController(ILogger logger)
+        {
+            _logger = logger;
+        }
+
+        // [TODO] MvcSandbox.Controllers > HomeController > Title
+        public string Title { get; set; }
+
+        // [TODO] MvcSandbox.Controllers > HomeController > Index
+        [HttpGet]
+        public IActionResult Index()
+        {
+            return View();
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "c-sharp")
+}
+
+#[test]
+fn test_block_namespace_with_nested_types() {
+    let source_code = indoc! {r#"
+    namespace Microsoft.AspNetCore.Http
+    {
+        public interface IResult
+        {
+            Task ExecuteAsync(HttpContext httpContext);
+        }
+
+        public readonly struct PathString
+        {
+            public bool HasValue() => !string.IsNullOrEmpty(Value);
+        }
+
+        public record Endpoint(string DisplayName);
+
+        public enum SameSiteMode
+        {
+            None,
+            Lax,
+        }
+    }"#};
+
+    let result = indoc! {r#"
+    // [TODO] Microsoft.AspNetCore.Http
+    namespace Microsoft.AspNetCore.Http
+    {
+        // [TODO] Microsoft.AspNetCore.Http > IResult
+        public interface IResult
+        {
+            // [TODO] Microsoft.AspNetCore.Http > IResult > ExecuteAsync
+            Task ExecuteAsync(HttpContext httpContext);
+        }
+
+        // [TODO] Microsoft.AspNetCore.Http > PathString
+        public readonly struct PathString
+        {
+            // [TODO] Microsoft.AspNetCore.Http > PathString > HasValue
+            public bool HasValue() => !string.IsNullOrEmpty(Value);
+        }
+
+        // [TODO] Microsoft.AspNetCore.Http > Endpoint
+        public record Endpoint(string DisplayName);
+
+        // [TODO] Microsoft.AspNetCore.Http > SameSiteMode
+        public enum SameSiteMode
+        {
+            None,
+            Lax,
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "c-sharp")
+}
@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod phoenix_case_test;
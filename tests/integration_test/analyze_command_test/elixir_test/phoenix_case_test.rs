@@ -0,0 +1,107 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_module_with_functions_and_nested_module() {
+    let source_code = indoc! {r#"
+    defmodule MyApp.Accounts do
+      @moduledoc """
+      The Accounts context.
+      """
+
+      alias MyApp.Repo
+
+      def list_users do
+        Repo.all(User)
+      end
+
+      def create_user(attrs \\ %{}) do
+        %User{}
+        |> User.changeset(attrs)
+        |> Repo.insert()
+      end
+
+      defp normalize(email) when is_binary(email) do
+        String.downcase(email)
+      end
+
+      defmacro __using__(_opts) do
+        quote do
+          import MyApp.Accounts
+        end
+      end
+
+      defmodule User do
+        def changeset(user, attrs), do: user
+      end
+    end"#};
+
+    let result = indoc! {r#"
+    # [TODO] MyApp.Accounts
+    defmodule MyApp.Accounts do
+      @moduledoc """
+      The Accounts context.
+      """
+
+      alias MyApp.Repo
+
+      # [TODO] MyApp.Accounts > list_users
+      def list_users do
+        Repo.all(User)
+      end
+
+      # [TODO] MyApp.Accounts > create_user
+      def create_user(attrs \\ %{}) do
+        %User{}
+        |> User.changeset(attrs)
+        |> Repo.insert()
+      end
+
+      # [TODO] MyApp.Accounts > normalize
+      defp normalize(email) when is_binary(email) do
+        String.downcase(email)
+      end
+
+      # [TODO] MyApp.Accounts > __using__
+      defmacro __using__(_opts) do
+        quote do
+          import MyApp.Accounts
+        end
+      end
+
+      # [TODO] MyApp.Accounts > User
+      defmodule User do
+        # [TODO] MyApp.Accounts > User > changeset
+        def changeset(user, attrs), do: user
+      end
+    end"#};
+
+    assert_analyzed_source_code(source_code, result, "elixir")
+}
+
+#[test]
+fn test_protocol_and_implementation() {
+    let source_code = indoc! {r#"
+    defprotocol Phoenix.Param do
+      def to_param(term)
+    end
+
+    defimpl Phoenix.Param, for: Integer do
+      def to_param(int), do: Integer.to_string(int)
+    end"#};
+
+    let result = indoc! {r#"
+    # [TODO] Phoenix.Param
+    defprotocol Phoenix.Param do
+      # [TODO] Phoenix.Param > to_param
+      def to_param(term)
+    end
+
+    # [TODO] Phoenix.Param
+    defimpl Phoenix.Param, for: Integer do
+      # [TODO] Phoenix.Param > to_param
+      def to_param(int), do: Integer.to_string(int)
+    end"#};
+
+    assert_analyzed_source_code(source_code, result, "elixir")
+}
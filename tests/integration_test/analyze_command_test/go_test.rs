@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod gin_case_test;
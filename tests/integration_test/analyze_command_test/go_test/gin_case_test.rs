@@ -0,0 +1,132 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_struct_interface_and_function_declarations() {
+    let source_code = indoc! {r#"
+    package gin
+
+    import (
+    	"net/http"
+    	"sync"
+    )
+
+    const defaultMultipartMemory = 32 << 20 // 32 MB
+
+    // Engine is the framework's instance.
+    type Engine struct {
+    	RouterGroup
+    	pool sync.Pool
+    }
+
+    type IRoutes interface {
+    	Use(...HandlerFunc) IRoutes
+    }
+
+    // New returns a new blank Engine instance.
+    func New() *Engine {
+    	engine := &Engine{}
+    	return engine
+    }"#};
+
+    let result = indoc! {r#"
+    package gin
+
+    import (
+    	"net/http"
+    	"sync"
+    )
+
+    const defaultMultipartMemory = 32 << 20 // 32 MB
+
+    // [TODO] Engine
+
+    // Engine is the framework's instance.
+    type Engine struct {
+    	RouterGroup
+    	pool sync.Pool
+    }
+
+    // [TODO] IRoutes
+    type IRoutes interface {
+    	Use(...HandlerFunc) IRoutes
+    }
+
+    // [TODO] New
+
+    // New returns a new blank Engine instance.
+    func New() *Engine {
+    	engine := &Engine{}
+    	return engine
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "go")
+}
+
+#[test]
+fn test_methods_with_receivers() {
+    let source_code = indoc! {r#"
+    package gin
+
+    func (engine *Engine) ServeHTTP(w http.ResponseWriter, req *http.Request) {
+    	c := engine.pool.Get().(*Context)
+    	engine.handleHTTPRequest(c)
+    }
+
+    func (group RouterGroup) BasePath() string {
+    	return group.basePath
+    }
+
+    func (s *Stack[T]) Push(value T) {
+    	s.items = append(s.items, value)
+    }"#};
+
+    let result = indoc! {r#"
+    package gin
+
+    // [TODO] Engine > ServeHTTP
+    func (engine *Engine) ServeHTTP(w http.ResponseWriter, req *http.Request) {
+    	c := engine.pool.Get().(*Context)
+    	engine.handleHTTPRequest(c)
+    }
+
+    // [TODO] RouterGroup > BasePath
+    func (group RouterGroup) BasePath() string {
+    	return group.basePath
+    }
+
+    // [TODO] Stack > Push
+    func (s *Stack[T]) Push(value T) {
+    	s.items = append(s.items, value)
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "go")
+}
+
+#[test]
+fn test_grouped_type_declarations() {
+    let source_code = indoc! {r#"
+    package gin
+
+    type HandlerFunc func(*Context)
+
+    type (
+    	OptionFunc func(*Engine)
+    	HandlersChain = []HandlerFunc
+    )"#};
+
+    let result = indoc! {r#"
+    package gin
+
+    // [TODO] HandlerFunc
+    type HandlerFunc func(*Context)
+
+    type (
+    	// [TODO] OptionFunc
+    	OptionFunc func(*Engine)
+    	// [TODO] HandlersChain
+    	HandlersChain = []HandlerFunc
+    )"#};
+
+    assert_analyzed_source_code(source_code, result, "go")
+}
@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod spring_boot_case_test;
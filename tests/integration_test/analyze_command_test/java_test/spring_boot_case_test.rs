@@ -0,0 +1,117 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_annotated_class_with_nested_class() {
+    let source_code = indoc! {r#"
+    package org.springframework.boot;
+
+    import java.util.List;
+
+    @SpringBootApplication
+    public class Application {
+        private final String name;
+
+        public Application(String name) {
+            this.name = name;
+        }
+
+        @Override
+        public String toString() {
+            return name;
+        }
+
+        public static class Builder {
+            public Application build() {
+                return new Application("app");
+            }
+        }
+    }"#};
+
+    let result = indoc! {r#"
+    package org.springframework.boot;
+
+    import java.util.List;
+
+    // [TODO] Application
+    @SpringBootApplication
+    public class Application {
+        private final String name;
+
+        // [TODO] Application > Application
+        public Application(String name) {
+            this.name = name;
+        }
+
+        // [TODO] Application > toString
+        @Override
+        public String toString() {
+            return name;
+        }
+
+        // [TODO] Application > Builder
+        public static class Builder {
+            // [TODO] Application > Builder > build
+            public Application build() {
+                return new Application("app");
+            }
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "java")
+}
+
+#[test]
+fn test_interface_enum_and_record_declarations() {
+    let source_code = indoc! {r#"
+    public interface WebServer {
+        void start() throws WebServerException;
+
+        default int getPort() {
+            return -1;
+        }
+    }
+
+    public enum WebApplicationType {
+        NONE,
+        SERVLET,
+        REACTIVE;
+
+        static WebApplicationType deduceFromClasspath() {
+            return SERVLET;
+        }
+    }
+
+    public record Pair(String first, String second) {
+    }"#};
+
+    let result = indoc! {r#"
+    // [TODO] WebServer
+    public interface WebServer {
+        // [TODO] WebServer > start
+        void start() throws WebServerException;
+
+        // [TODO] WebServer > getPort
+        default int getPort() {
+            return -1;
+        }
+    }
+
+    // [TODO] WebApplicationType
+    public enum WebApplicationType {
+        NONE,
+        SERVLET,
+        REACTIVE;
+
+        // [TODO] WebApplicationType > deduceFromClasspath
+        static WebApplicationType deduceFromClasspath() {
+            return SERVLET;
+        }
+    }
+
+    // [TODO] Pair
+    public record Pair(String first, String second) {
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "java")
+}
@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod ktor_case_test;
@@ -0,0 +1,97 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_annotated_class_and_interface_declarations() {
+    let source_code = indoc! {r#"
+    package io.ktor.server.engine
+
+    import io.ktor.server.application.*
+
+    @Serializable
+    data class EngineConnectorConfig(val port: Int)
+
+    interface ApplicationEngine {
+        fun start(wait: Boolean = false): ApplicationEngine
+
+        open class Configuration {
+            var parallelism: Int = 1
+        }
+    }"#};
+
+    let result = indoc! {r#"
+    package io.ktor.server.engine
+
+    import io.ktor.server.application.*
+
+    // [TODO] EngineConnectorConfig
+    @Serializable
+    data class EngineConnectorConfig(val port: Int)
+
+    // [TODO] ApplicationEngine
+    interface ApplicationEngine {
+        // [TODO] ApplicationEngine > start
+        fun start(wait: Boolean = false): ApplicationEngine
+
+        // [TODO] ApplicationEngine > Configuration
+        open class Configuration {
+            var parallelism: Int = 1
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "kotlin")
+}
+
+#[test]
+fn test_enum_class_object_and_companion_object() {
+    let source_code = indoc! {r#"
+    enum class HttpMethodKind {
+        GET,
+        POST;
+
+        fun isSafe(): Boolean = this == GET
+    }
+
+    object EngineMain {
+        @JvmStatic
+        fun main(args: Array<String>) {
+            println(args)
+        }
+    }
+
+    class NettyApplicationEngine {
+        companion object {
+            fun create(): NettyApplicationEngine = NettyApplicationEngine()
+        }
+    }"#};
+
+    let result = indoc! {r#"
+    // [TODO] HttpMethodKind
+    enum class HttpMethodKind {
+        GET,
+        POST;
+
+        // [TODO] HttpMethodKind > isSafe
+        fun isSafe(): Boolean = this == GET
+    }
+
+    // [TODO] EngineMain
+    object EngineMain {
+        // [TODO] EngineMain > main
+        @JvmStatic
+        fun main(args: Array<String>) {
+            println(args)
+        }
+    }
+
+    // [TODO] NettyApplicationEngine
+    class NettyApplicationEngine {
+        // [TODO] NettyApplicationEngine > companion
+        companion object {
+            // [TODO] NettyApplicationEngine > companion > create
+            fun create(): NettyApplicationEngine = NettyApplicationEngine()
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "kotlin")
+}
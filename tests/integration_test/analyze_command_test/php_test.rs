@@ -0,0 +1,2 @@
+#[cfg(test)]
+mod laravel_case_test;
@@ -0,0 +1,125 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_namespace_class_and_trait_declarations() {
+    let source_code = indoc! {r#"
+    <?php
+
+    namespace Illuminate\Support;
+
+    use Closure;
+
+    trait Macroable
+    {
+        protected static $macros = [];
+
+        public static function macro($name, $macro)
+        {
+            static::$macros[$name] = $macro;
+        }
+    }
+
+    interface Htmlable
+    {
+        public function toHtml();
+    }
+
+    class Collection implements Htmlable
+    {
+        use Macroable;
+
+        public function toHtml()
+        {
+            return '';
+        }
+    }
+
+    function collect($value = [])
+    {
+        return new Collection($value);
+    }"#};
+
+    let result = indoc! {r#"
+    <?php
+
+    // [TODO] Illuminate\Support
+    namespace Illuminate\Support;
+
+    use Closure;
+
+    // [TODO] Illuminate\Support > Macroable
+    trait Macroable
+    {
+        protected static $macros = [];
+
+        // [TODO] Illuminate\Support > Macroable > macro
+        public static function macro($name, $macro)
+        {
+            static::$macros[$name] = $macro;
+        }
+    }
+
+    // [TODO] Illuminate\Support > Htmlable
+    interface Htmlable
+    {
+        // [TODO] Illuminate\Support > Htmlable > toHtml
+        public function toHtml();
+    }
+
+    // [TODO] Illuminate\Support > Collection
+    class Collection implements Htmlable
+    {
+        use Macroable;
+
+        // [TODO] Illuminate\Support > Collection > toHtml
+        public function toHtml()
+        {
+            return '';
+        }
+    }
+
+    // [TODO] Illuminate\Support > collect
+    function collect($value = [])
+    {
+        return new Collection($value);
+    }"#};
+
+    assert_analyzed_source_code(source_code, result, "php")
+}
+
+#[test]
+fn test_template_with_html_is_left_unchanged() {
+    let source_code = indoc! {r#"
+    <!DOCTYPE html>
+    <html>
+    <?php function greeting($name) { return "Hello, $name"; } ?>
+    <body>
+    <?php
+    function farewell($name)
+    {
+        return "Bye, $name";
+    }
+    ?>
+    <p><?php echo greeting('world'); ?></p>
+    </body>
+    </html>"#};
+
+    let result = indoc! {r#"
+    <!DOCTYPE html>
+    <html>
+    <?php function greeting($name) { return "Hello, $name"; } ?>
+    <body>
+    <?php
+    // [TODO] farewell
+    function farewell($name)
+    {
+        return "Bye, $name";
+    }
+    ?>
+    <p><?php echo greeting('world'); ?></p>
+    </body>
+    </html>"#};
+
+    assert_analyzed_source_code(source_code, result, "php")
+}
@@ -20,7 +20,7 @@ fn test_declaring_error_enum_with_macro() {
     let result = indoc! {r#"
     use thiserror::Error;
 
-    /// [TODO] FormatError
+    // [TODO] FormatError
     #[derive(Error, Debug)]
     pub enum FormatError {
         #[error("Invalid header (expected {expected:?}, got {found:?})")]
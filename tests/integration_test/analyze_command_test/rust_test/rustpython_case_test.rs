@@ -26,7 +26,7 @@ fn test_declaring_enum_with_stacked_attribute() {
     }"#};
 
     let result = indoc! {r#"
-    /// [TODO] JitCompileError
+    // [TODO] JitCompileError
     #[derive(Debug, thiserror::Error)]
     #[non_exhaustive]
     pub enum JitCompileError {
@@ -38,7 +38,7 @@ fn test_declaring_enum_with_stacked_attribute() {
         CraneliftError(#[from] ModuleError),
     }
 
-    /// [TODO] JitArgumentError
+    // [TODO] JitArgumentError
     #[derive(Debug, thiserror::Error, Eq, PartialEq)]
     #[non_exhaustive]
     pub enum JitArgumentError {
@@ -23,25 +23,25 @@ fn test_several_impl_declaration() {
     }"};
 
     let result = indoc! {"
-    /// [TODO] PartialEq<Symbol>
+    // [TODO] PartialEq<Symbol>
     impl PartialEq<Symbol> for Ident {
-        /// [TODO] PartialEq<Symbol> > eq
+        // [TODO] PartialEq<Symbol> > eq
         fn eq(&self, word: &Symbol) -> bool {
             self == word.0
         }
     }
 
-    /// [TODO] PartialEq<Symbol>
+    // [TODO] PartialEq<Symbol>
     impl<'a> PartialEq<Symbol> for &'a Ident {
-        /// [TODO] PartialEq<Symbol> > eq
+        // [TODO] PartialEq<Symbol> > eq
         fn eq(&self, word: &Symbol) -> bool {
             *self == word.0
         }
     }
 
-    /// [TODO] PartialEq<Symbol>
+    // [TODO] PartialEq<Symbol>
     impl PartialEq<Symbol> for Path {
-        /// [TODO] PartialEq<Symbol> > eq
+        // [TODO] PartialEq<Symbol> > eq
         fn eq(&self, word: &Symbol) -> bool {
             self.is_ident(word.0)
         }
@@ -9,3 +9,6 @@ mod async_case_test;
 
 #[cfg(test)]
 mod svelt_case_test;
+
+#[cfg(test)]
+mod declaration_case_test;
@@ -20,7 +20,6 @@ fn test_async_function_expression() {
 }
 
 #[test]
-#[ignore = "TODO: Support arrow function"]
 fn test_async_arrow_function() {
     let source_code = indoc! {r#"
     const foo = async () => {
@@ -37,3 +36,21 @@ fn test_async_arrow_function() {
 
     assert_analyzed_source_code(source_code, expected, "typescript")
 }
+
+#[test]
+fn test_exported_async_arrow_function() {
+    let source_code = indoc! {r#"
+    const TIMEOUT = 1000;
+    export const handler = async (event: Event) => {
+        return await process(event, TIMEOUT);
+    }"#};
+
+    let expected = indoc! {r#"
+    const TIMEOUT = 1000;
+    // [TODO] handler
+    export const handler = async (event: Event) => {
+        return await process(event, TIMEOUT);
+    }"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
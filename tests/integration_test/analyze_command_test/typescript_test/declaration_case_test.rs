@@ -0,0 +1,205 @@
+use crate::integration_test::assert_analyzed_source_code;
+use indoc::indoc;
+
+#[test]
+fn test_type_alias_declaration() {
+    let source_code = indoc! {r#"
+    type Listener<T> = (event: T) => void;
+
+    type Point = {
+        x: number;
+        y: number;
+    };"#};
+
+    let expected = indoc! {r#"
+    // [TODO] Listener
+    type Listener<T> = (event: T) => void;
+
+    // [TODO] Point
+    type Point = {
+        x: number;
+        y: number;
+    };"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
+
+#[test]
+fn test_abstract_class_declaration() {
+    let source_code = indoc! {r#"
+    abstract class Shape {
+        abstract area(): number;
+
+        describe(): string {
+            return `area: ${this.area()}`;
+        }
+    }"#};
+
+    let expected = indoc! {r#"
+    // [TODO] Shape
+    abstract class Shape {
+        // [TODO] Shape > area
+        abstract area(): number;
+
+        // [TODO] Shape > describe
+        describe(): string {
+            return `area: ${this.area()}`;
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
+
+#[test]
+fn test_namespace_and_module_declarations() {
+    let source_code = indoc! {r#"
+    namespace Validation {
+        function isEmpty(value: string): boolean {
+            return value.length === 0;
+        }
+    }
+
+    declare module "express" {
+        function json(): Handler;
+    }"#};
+
+    let expected = indoc! {r#"
+    // [TODO] Validation
+    namespace Validation {
+        // [TODO] Validation > isEmpty
+        function isEmpty(value: string): boolean {
+            return value.length === 0;
+        }
+    }
+
+    // [TODO] express
+    declare module "express" {
+        // [TODO] express > json
+        function json(): Handler;
+    }"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
+
+#[test]
+fn test_exported_constants() {
+    let source_code = indoc! {r#"
+    export const DEFAULT_TIMEOUT = 1000;
+    export let retries = 3, delay = 100;
+
+    export const fetchPost = function (id: number) {
+        return request(`/posts/${id}`);
+    };"#};
+
+    let expected = indoc! {r#"
+    export const DEFAULT_TIMEOUT = 1000;
+    export let retries = 3, delay = 100;
+
+    // [TODO] fetchPost
+    export const fetchPost = function (id: number) {
+        return request(`/posts/${id}`);
+    };"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
+
+#[test]
+fn test_exports_without_declaration() {
+    let source_code = indoc! {r#"
+    function parse(input: string): Node {
+        return createParser(input).parse();
+    }
+
+    function reset(): void {}
+
+    export { parse, reset };
+    export default parse;
+    export = reset;"#};
+
+    let expected = indoc! {r#"
+    // [TODO] parse
+    function parse(input: string): Node {
+        return createParser(input).parse();
+    }
+
+    // [TODO] reset
+    function reset(): void {}
+
+    export { parse, reset };
+    export default parse;
+    export = reset;"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
+
+#[test]
+fn test_overload_signatures() {
+    let source_code = indoc! {r#"
+    function parse(input: string): Node;
+    function parse(input: Buffer): Node;
+    function parse(input: string | Buffer): Node {
+        return createParser(input).parse();
+    }
+
+    class Parser {
+        reset(): void;
+        reset(input: string): void;
+        reset(input?: string): void {
+            this.input = input ?? "";
+        }
+    }"#};
+
+    let expected = indoc! {r#"
+    // [TODO] parse
+    function parse(input: string): Node;
+    function parse(input: Buffer): Node;
+    function parse(input: string | Buffer): Node {
+        return createParser(input).parse();
+    }
+
+    // [TODO] Parser
+    class Parser {
+        // [TODO] Parser > reset
+        reset(): void;
+        reset(input: string): void;
+        reset(input?: string): void {
+            this.input = input ?? "";
+        }
+    }"#};
+
+    assert_analyzed_source_code(source_code, expected, "typescript")
+}
+
+#[test]
+fn test_tsx_component() {
+    let source_code = indoc! {r#"
+    interface ButtonProps {
+        label: string;
+    }
+
+    const Button = ({ label }: ButtonProps) => {
+        return <button className="primary">{label}</button>;
+    };
+
+    function App() {
+        return <Button label="Save" />;
+    }"#};
+
+    let expected = indoc! {r#"
+    // [TODO] ButtonProps
+    interface ButtonProps {
+        label: string;
+    }
+
+    // [TODO] Button
+    const Button = ({ label }: ButtonProps) => {
+        return <button className="primary">{label}</button>;
+    };
+
+    // [TODO] App
+    function App() {
+        return <Button label="Save" />;
+    }"#};
+
+    assert_analyzed_source_code(source_code, expected, "tsx")
+}
@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod language_test {
+    use balpan::language::{
+        Language, LanguageDefinition, MarkerConfiguration, MarkerPlacement, TestCode,
+        TraversableNode,
+    };
+    use indoc::indoc;
+
+    #[test]
+    fn test_c_source_and_header_files() {
+        assert!(Language::from_extension("c") == Language::C);
+        assert!(Language::from_extension("h") == Language::C);
+        assert!(Language::from_extension("hpp") == Language::Cpp);
+    }
+
+    #[test]
+    fn test_c_header_with_cplusplus_guard() {
+        let source_code = indoc! {r#"
+        #ifdef __cplusplus
+        extern "C" {
+        #endif
+
+        /* Create a new list. See listCreate() in adlist.c:: for details */
+        list *listCreate(void);
+
+        #ifdef __cplusplus
+        }
+        #endif"#};
+
+        assert!(Language::from_source_code("h", source_code) == Language::C);
+    }
+
+    #[test]
+    fn test_c_header_with_cpp_like_text() {
+        let source_code = indoc! {r#"
+        // Keep in sync with Config::load() of the C++ client
+        #define SEPARATOR "::"
+
+        struct template_engine {
+            int class_count; /* see namespace docs { */
+        };
+
+        int template_render(struct template_engine *engine); // class Foo {"#};
+
+        assert!(Language::from_source_code("h", source_code) == Language::C);
+    }
+
+    #[test]
+    fn test_cpp_header() {
+        let source_code = indoc! {r#"
+        #include <string>
+
+        namespace bmqimp {
+        class Application {
+          public:
+            std::string name() const;
+        };
+        }"#};
+
+        assert!(Language::from_source_code("h", source_code) == Language::Cpp);
+    }
+
+    #[test]
+    fn test_cpp_only_constructs_in_header() {
+        let headers = [
+            "template <typename T>\nT max(T a, T b);",
+            "class Buffer : public Base {\n};",
+            "namespace util {\nint parse(const char *s);\n}",
+            "extern \"C++\" {\nint parse(const char *s);\n}",
+        ];
+
+        for source_code in headers {
+            assert!(Language::from_source_code("h", source_code) == Language::Cpp);
+        }
+    }
+
+    #[test]
+    fn test_javascript_and_typescript_extensions() {
+        assert!(Language::from_extension("mjs") == Language::JavaScript);
+        assert!(Language::from_extension("cjs") == Language::JavaScript);
+        assert!(Language::from_extension("jsx") == Language::Jsx);
+        assert!(Language::from_extension("mts") == Language::TypeScript);
+        assert!(Language::from_extension("cts") == Language::TypeScript);
+        assert!(Language::from_extension("tsx") == Language::Tsx);
+
+        assert_eq!(Language::Jsx.grammar_name(), "javascript");
+        assert_eq!(Language::Tsx.grammar_name(), "tsx");
+        assert_eq!(Language::TypeScript.grammar_name(), "typescript");
+        assert!(Language::Tsx
+            .commentable_node_types()
+            .contains(&"type_alias_declaration"));
+    }
+
+    #[test]
+    fn test_language_definitions_from_languages_toml() {
+        let rust = Language::from_extension("rs");
+
+        assert!(rust == Language::Rust);
+        assert_eq!(rust.top_level_node_type(), "source_file");
+        assert_eq!(rust.decorator_node_type(), "attribute_item");
+        assert_eq!(rust.todo_comment_token(), Some("///"));
+        assert!(rust.commentable_node_types().contains(&"function_item"));
+        assert!(rust.nested_traversable_symbols().contains(&"impl_item"));
+
+        assert_eq!(Language::Ruby.indent_unit(), "  ");
+        assert_eq!(Language::Python.todo_comment_token(), Some("#"));
+    }
+
+    #[test]
+    fn test_languages_without_commentable_nodes_are_not_supported() {
+        let toml = Language::from_extension("toml");
+
+        assert!(toml == Language::Other("toml".to_string()));
+        assert!(!toml.is_supported());
+        assert_eq!(toml.todo_comment_token(), None);
+        assert!(Language::Go.is_supported());
+    }
+
+    #[test]
+    fn test_marker_placement() {
+        assert_eq!(
+            Language::Python.marker_placement(),
+            MarkerPlacement::AboveDecorators
+        );
+
+        let configuration: MarkerConfiguration =
+            toml::from_str("placement = \"start-of-body\"").unwrap();
+        let fallback = MarkerConfiguration {
+            placement: Some(MarkerPlacement::AboveDeclaration),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            configuration.or(&fallback).placement,
+            Some(MarkerPlacement::StartOfBody)
+        );
+        assert_eq!(
+            MarkerConfiguration::default().or(&fallback).placement,
+            Some(MarkerPlacement::AboveDeclaration)
+        );
+    }
+
+    #[test]
+    fn test_test_patterns() {
+        let patterns = Language::Rust.test_patterns();
+        let is_test_attribute =
+            |text: &str| patterns.attributes.iter().any(|regex| regex.is_match(text));
+
+        assert!(is_test_attribute("#[test]"));
+        assert!(is_test_attribute("#[cfg(test)]"));
+        assert!(is_test_attribute(
+            "#[tokio::test(flavor = \"multi_thread\")]"
+        ));
+        assert!(!is_test_attribute("#[cfg(feature = \"test\")]"));
+        assert!(!is_test_attribute("#[attest]"));
+
+        assert!(Language::JavaScript
+            .test_patterns()
+            .calls
+            .contains(&"describe".to_string()));
+        assert!(Language::from("toml").test_patterns().is_empty());
+        assert_eq!(Language::Rust.test_code(), TestCode::Annotate);
+
+        let configuration: MarkerConfiguration = toml::from_str("test-code = \"skip\"").unwrap();
+        assert_eq!(configuration.test_code, Some(TestCode::Skip));
+    }
+
+    #[test]
+    fn test_traversable_nodes() {
+        assert!(Language::Rust.is_traversable("impl_item", 3));
+        assert!(!Language::Rust.is_traversable("trait_item", 0));
+        assert_eq!(Language::Rust.max_depth(), None);
+
+        let definition: LanguageDefinition = toml::from_str(indoc! {r#"
+            name = "python"
+            nested-traversable-nodes = [
+              "class_definition",
+              { kind = "function_definition", max-depth = 1 },
+            ]
+            max-depth = 2
+        "#})
+        .unwrap();
+
+        assert_eq!(definition.max_depth, Some(2));
+        assert_eq!(
+            definition.nested_traversable_nodes,
+            vec![
+                TraversableNode::Kind("class_definition".to_string()),
+                TraversableNode::Limited {
+                    kind: "function_definition".to_string(),
+                    max_depth: 1,
+                },
+            ]
+        );
+        assert_eq!(definition.nested_traversable_nodes[1].max_depth(), Some(1));
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod strip_test {
+    use balpan::commands::strip::strip_todo_comments;
+    use balpan::grammar::{build_grammars, fetch_grammars};
+    use balpan::language::Language;
+    use indoc::indoc;
+
+    fn strip(source_code: &str, language: &Language) -> String {
+        fetch_grammars().unwrap();
+        build_grammars(None).unwrap();
+
+        strip_todo_comments(source_code, language).unwrap()
+    }
+
+    #[test]
+    fn test_strip_todo_and_done_comments() {
+        let source_code = indoc! {r#"
+        # [TODO] Post
+        class Post:
+            # [DONE] Post > author
+            def author(self):
+                pass
+
+        # [DONE] main
+        def main():
+            pass"#};
+
+        let expected = indoc! {r#"
+        class Post:
+            def author(self):
+                pass
+
+        def main():
+            pass"#};
+
+        assert_eq!(strip(source_code, &Language::Python), expected);
+    }
+
+    #[test]
+    fn test_keep_comments_which_are_not_generated_by_balpan() {
+        let source_code = indoc! {r#"
+        // [TODO] tree_sitter_extended
+        /// Extensions of the treesitter node
+        mod tree_sitter_extended {
+            // [TODO]: handle the lifetime
+            /// [TODO] tree_sitter_extended > RangeFactory
+            // [REVIEW] tree_sitter_extended > RangeFactory
+            // [DONE] tree_sitter_extended > RangeFactory
+            pub trait RangeFactory {}
+        }"#};
+
+        let expected = indoc! {r#"
+        /// Extensions of the treesitter node
+        mod tree_sitter_extended {
+            // [TODO]: handle the lifetime
+            /// [TODO] tree_sitter_extended > RangeFactory
+            // [REVIEW] tree_sitter_extended > RangeFactory
+            pub trait RangeFactory {}
+        }"#};
+
+        assert_eq!(strip(source_code, &Language::Rust), expected);
+    }
+
+    #[test]
+    fn test_keep_hand_written_comments_like_todo_comments() {
+        let source_code = indoc! {r#"
+        // [TODO] handle timeouts
+        function fetchPost(id) {
+            // [TODO] retry on failure
+            return request(id);
+        }"#};
+
+        let analyzed = indoc! {r#"
+        // [TODO] handle timeouts
+        // [TODO] fetchPost
+        function fetchPost(id) {
+            // [TODO] retry on failure
+            return request(id);
+        }"#};
+
+        assert_eq!(strip(analyzed, &Language::JavaScript), source_code);
+    }
+
+    #[test]
+    fn test_strip_legacy_doc_comment_markers() {
+        let source_code = indoc! {r#"
+        /// Parses the source code
+        /// [DONE] parse
+        pub fn parse() {}
+
+        // [TODO] write
+        pub fn write() {}"#};
+
+        let expected = indoc! {r#"
+        /// Parses the source code
+        pub fn parse() {}
+
+        pub fn write() {}"#};
+
+        assert_eq!(strip(source_code, &Language::Rust), expected);
+    }
+
+    #[test]
+    fn test_strip_preserves_line_endings() {
+        let source_code =
+            "// [TODO] foo\r\nfunction foo() {\r\n}\r\n\r\n// [DONE] bar\r\nconst bar = 1;";
+        let expected = "function foo() {\r\n}\r\n\r\nconst bar = 1;";
+
+        assert_eq!(strip(source_code, &Language::JavaScript), expected);
+
+        let source_code = "// [TODO] foo\nfunction foo() {}\n";
+        let expected = "function foo() {}\n";
+
+        assert_eq!(strip(source_code, &Language::TypeScript), expected);
+    }
+
+    #[test]
+    fn test_strip_keeps_bom() {
+        let source_code = "\u{feff}# [TODO] main\r\ndef main():\r\n    pass";
+        let expected = "\u{feff}def main():\r\n    pass";
+
+        assert_eq!(strip(source_code, &Language::Python), expected);
+    }
+
+    #[test]
+    fn test_strip_unsupported_language_keeps_source_code() {
+        let source_code = "# [TODO] foo\nfoo:\n\techo foo\n";
+
+        assert_eq!(
+            strip(source_code, &Language::Other("make".to_string())),
+            source_code
+        );
+    }
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod symbols_test {
+    use balpan::grammar::{build_grammars, fetch_grammars, get_language};
+    use balpan::language::Language;
+    use balpan::symbols::{find_symbol_definitions, symbols_query_source};
+    use indoc::indoc;
+    use tree_sitter::{Parser, Query};
+
+    #[test]
+    fn test_builtin_symbols_queries() {
+        let query_source = symbols_query_source("rust").unwrap();
+
+        assert!(query_source.contains("@definition.function"));
+        assert!(query_source.contains("@name"));
+        assert!(symbols_query_source("make").is_none());
+    }
+
+    #[test]
+    fn test_find_symbol_definitions_with_query() {
+        fetch_grammars().unwrap();
+        build_grammars(None).unwrap();
+
+        let source_code = indoc! {"
+            mod foo;
+
+            impl Display for Wrapper {
+                fn fmt(&self) {}
+            }
+
+            impl Wrapper {}"};
+
+        let language = get_language("rust").unwrap();
+        let mut parser = Parser::new();
+        parser.set_language(language).unwrap();
+        let tree = parser.parse(source_code, None).unwrap();
+
+        let query = Query::new(language, &symbols_query_source("rust").unwrap()).unwrap();
+        let definitions = find_symbol_definitions(&query, tree.root_node(), source_code);
+        let mut names: Vec<&str> = definitions
+            .values()
+            .map(|name| name.as_deref().unwrap())
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["Display", "Wrapper", "fmt"]);
+    }
+
+    fn symbol_names(language_name: &str, source_code: &str) -> Vec<String> {
+        fetch_grammars().unwrap();
+        build_grammars(None).unwrap();
+
+        let language = get_language(language_name).unwrap();
+        let mut parser = Parser::new();
+        parser.set_language(language).unwrap();
+        let tree = parser.parse(source_code, None).unwrap();
+
+        let query = Query::new(language, &symbols_query_source(language_name).unwrap()).unwrap();
+        let definitions = find_symbol_definitions(&query, tree.root_node(), source_code);
+        let mut names: Vec<String> = definitions
+            .into_values()
+            .map(|name| name.unwrap_or_else(|| "anonymous".to_string()))
+            .collect();
+        names.sort();
+
+        names
+    }
+
+    #[test]
+    fn test_c_function_definitions_are_named_after_their_declarators() {
+        let source_code = indoc! {"
+            int sqlite3_strglob(zGlob, zStr)
+              const char *zGlob;
+              const char *zStr;
+            {
+              return 0;
+            }
+
+            static char *sqlite3StrDup(const char *z) {
+              return 0;
+            }
+
+            char **split(const char *z) {
+              return 0;
+            }"};
+
+        assert_eq!(
+            symbol_names("c", source_code),
+            vec!["split", "sqlite3StrDup", "sqlite3_strglob"]
+        );
+    }
+
+    #[test]
+    fn test_c_specifiers_and_type_definitions() {
+        let source_code = indoc! {"
+            struct sqlite3_vfs;
+
+            struct Mem { int n; };
+            union Aux { void *p; };
+            enum PagerState { PAGER_OPEN };
+            enum { ANONYMOUS };
+
+            typedef struct Vdbe Vdbe;
+            typedef char *string_t;
+            typedef int (*sqlite3_callback)(void *);"};
+
+        assert_eq!(
+            symbol_names("c", source_code),
+            vec![
+                "Aux",
+                "Mem",
+                "PagerState",
+                "Vdbe",
+                "anonymous",
+                "sqlite3_callback",
+                "string_t"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_javascript_declarations_and_exports() {
+        let source_code = indoc! {"
+            function getPackageName(file) {}
+
+            class KeyPressHandler {
+                startInterceptingKeyStrokes() {}
+            }
+
+            export function parse(source) {}
+            export class Parser {}
+            export const parse_expression_at = (source, index) => {};
+
+            export { parse as default };
+            export * from './parser';"};
+
+        assert_eq!(
+            symbol_names("javascript", source_code),
+            vec![
+                "KeyPressHandler",
+                "Parser",
+                "Parser",
+                "getPackageName",
+                "parse",
+                "parse",
+                "parse_expression_at",
+                "startInterceptingKeyStrokes"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_symbols_query_is_compiled_once_per_language() {
+        fetch_grammars().unwrap();
+        build_grammars(None).unwrap();
+
+        let grammar = get_language("rust").unwrap();
+        let query = Language::Rust.symbols_query(grammar).unwrap();
+
+        assert!(std::ptr::eq(
+            query,
+            Language::Rust.symbols_query(grammar).unwrap()
+        ));
+        assert!(Language::Ruby
+            .symbols_query(get_language("ruby").unwrap())
+            .is_none());
+    }
+}
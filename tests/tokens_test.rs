@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tokens_test {
+    use balpan::language::{Language, MarkerConfiguration, TestCode};
+    use std::path::Path;
+
+    use balpan::tokens::{file_search_patterns, search_patterns, Marker};
+
+    fn review_marker() -> MarkerConfiguration {
+        MarkerConfiguration {
+            template: Some("{comment} REVIEW({state}) {path}".to_string()),
+            todo_state: Some("PENDING".to_string()),
+            done_state: Some("OK".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_marker() {
+        let marker = Marker::new(&MarkerConfiguration::default(), Some("///")).unwrap();
+
+        assert_eq!(marker.render("TODO", "Foo > bar"), "/// [TODO] Foo > bar");
+        assert_eq!(
+            marker.parse("    /// [DONE] Foo > bar"),
+            Some(("DONE", "Foo > bar"))
+        );
+        assert_eq!(marker.parse("/// [TODO]"), Some(("TODO", "")));
+        assert_eq!(marker.parse("/// [WIP] Foo"), None);
+        assert_eq!(marker.parse("/// Returns [TODO] Foo"), None);
+    }
+
+    #[test]
+    fn test_custom_marker_template_and_states() {
+        let marker = Marker::new(&review_marker(), Some("//")).unwrap();
+
+        assert_eq!(marker.todo_state(), "PENDING");
+        assert_eq!(marker.render("PENDING", "main"), "// REVIEW(PENDING) main");
+        assert_eq!(
+            marker.parse("\t// REVIEW(OK) Engine > Run"),
+            Some(("OK", "Engine > Run"))
+        );
+        assert_eq!(marker.parse("// [TODO] main"), None);
+    }
+
+    #[test]
+    fn test_marker_without_comment_token() {
+        assert!(Marker::new(&MarkerConfiguration::default(), None).is_err());
+
+        let configuration = MarkerConfiguration {
+            template: Some("REVIEW({state}) {path}".to_string()),
+            ..Default::default()
+        };
+        assert!(Marker::new(&configuration, None).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_marker_template() {
+        let configuration = MarkerConfiguration {
+            template: Some("{comment} [{state}] {name}".to_string()),
+            ..Default::default()
+        };
+
+        assert!(Marker::new(&configuration, Some("#")).is_err());
+    }
+
+    #[test]
+    fn test_marker_of_language() {
+        let marker = Marker::from_language(&Language::Python).unwrap();
+
+        assert_eq!(marker.render("TODO", "Post"), "# [TODO] Post");
+        assert!(Marker::from_language(&Language::Other("brainfuck".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_search_patterns() {
+        assert_eq!(
+            search_patterns(&MarkerConfiguration::default()),
+            vec!["[TODO]", "[DONE]"]
+        );
+        assert_eq!(
+            search_patterns(&review_marker()),
+            vec!["REVIEW(PENDING)", "REVIEW(OK)"]
+        );
+    }
+
+    #[test]
+    fn test_file_search_patterns_follow_language() {
+        assert_eq!(
+            file_search_patterns(Path::new("src/main.rs")),
+            search_patterns(&Language::Rust.marker_configuration())
+        );
+        assert_eq!(
+            file_search_patterns(Path::new("Makefile")),
+            vec!["[TODO]", "[DONE]"]
+        );
+    }
+
+    #[test]
+    fn test_plain_comment_marker() {
+        let configuration = MarkerConfiguration {
+            plain_comment: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(configuration.comment_token(&Language::Rust), Some("//"));
+        assert_eq!(
+            MarkerConfiguration::default().comment_token(&Language::Rust),
+            Some("///")
+        );
+
+        let marker = Marker::new(&configuration, configuration.comment_token(&Language::Rust));
+        assert_eq!(marker.unwrap().render("TODO", "main"), "// [TODO] main");
+
+        let marker = Marker::from_language(&Language::Cpp).unwrap();
+        assert_eq!(marker.render("TODO", "main"), "// [TODO] main");
+    }
+
+    #[test]
+    fn test_parse_legacy_doc_comment_marker() {
+        let marker = Marker::from_language(&Language::Rust).unwrap();
+
+        assert_eq!(marker.parse("// [TODO] main"), Some(("TODO", "main")));
+        assert_eq!(
+            marker.parse("    /// [DONE] Foo > bar"),
+            Some(("DONE", "Foo > bar"))
+        );
+        assert_eq!(marker.parse("/// Returns [TODO] main"), None);
+
+        let marker = Marker::from_language(&Language::Python).unwrap();
+        assert_eq!(marker.parse("## [TODO] main"), None);
+    }
+
+    #[test]
+    fn test_plain_comment_marker_without_comment_token() {
+        let configuration = MarkerConfiguration {
+            plain_comment: Some(true),
+            ..Default::default()
+        };
+
+        for language in [Language::TypeScript, Language::Tsx, Language::Java] {
+            assert_eq!(language.comment_token(), None);
+            assert_eq!(configuration.comment_token(&language), Some("//"));
+            assert!(Marker::new(&configuration, configuration.comment_token(&language)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_test_state() {
+        let marker = Marker::new(&MarkerConfiguration::default(), Some("#")).unwrap();
+
+        assert_eq!(marker.test_state(), "TEST");
+        assert_eq!(
+            marker.parse("# [TEST] test_main"),
+            Some(("TEST", "test_main"))
+        );
+
+        let configuration = MarkerConfiguration {
+            test_state: Some("DONE".to_string()),
+            ..Default::default()
+        };
+        assert!(Marker::new(&configuration, Some("#")).is_err());
+
+        let configuration = MarkerConfiguration {
+            test_code: Some(TestCode::Mark),
+            ..Default::default()
+        };
+        assert_eq!(
+            search_patterns(&configuration),
+            vec!["[TODO]", "[DONE]", "[TEST]"]
+        );
+    }
+}